@@ -8,6 +8,29 @@ external compare : t -> t -> int = "caml_bigint_256_compare"
 
 external div : t -> t -> t = "caml_bigint_256_div"
 
+external rem : t -> t -> t = "caml_bigint_256_mod"
+
+(** [add x y] returns the sum modulo 2^256 and the carry flag. *)
+external add : t -> t -> t * bool = "caml_bigint_256_add"
+
+(** [sub x y] returns the difference modulo 2^256 and the borrow flag. *)
+external sub : t -> t -> t * bool = "caml_bigint_256_sub"
+
+(** [mul x y] returns the low and high halves of the 512-bit product. *)
+external mul : t -> t -> t * t = "caml_bigint_256_mul"
+
+external shift_left : t -> int -> t = "caml_bigint_256_shift_left"
+
+external shift_right : t -> int -> t = "caml_bigint_256_shift_right"
+
+external logand : t -> t -> t = "caml_bigint_256_logand"
+
+external logor : t -> t -> t = "caml_bigint_256_logor"
+
+external logxor : t -> t -> t = "caml_bigint_256_logxor"
+
+external num_bits : t -> int = "caml_bigint_256_num_bits"
+
 external test_bit : t -> int -> bool = "caml_bigint_256_test_bit"
 
 external print : t -> unit = "caml_bigint_256_print"
@@ -18,6 +41,14 @@ external of_numeral : string -> int -> int -> t = "caml_bigint_256_of_numeral"
 
 external of_decimal_string : string -> t = "caml_bigint_256_of_decimal_string"
 
+external of_hex_string : string -> t = "caml_bigint_256_of_hex_string"
+
+external to_hex_string : t -> string = "caml_bigint_256_to_hex_string"
+
+external of_int : int -> t = "caml_bigint_256_of_int"
+
+external to_int : t -> int = "caml_bigint_256_to_int"
+
 external to_bytes : t -> Bytes.t = "caml_bigint_256_to_bytes"
 
 external of_bytes : Bytes.t -> t = "caml_bigint_256_of_bytes"
@@ -45,3 +76,27 @@ let%test "test_bit" =
   (* z = 0b0000_0011 *)
   let z = div x y in
   test_bit z 0 && test_bit z 1 && not (test_bit z 2)
+
+let%test "add and sub" =
+  let x = of_decimal_string "5" in
+  let y = of_decimal_string "7" in
+  let z, carry = add x y in
+  let x', borrow = sub z y in
+  let _, borrow' = sub x y in
+  to_int z = 12 && (not carry) && compare x x' = 0 && (not borrow) && borrow'
+
+let%test "mul" =
+  let x = shift_left (of_int 1) 255 in
+  let lo, hi = mul x (of_int 4) in
+  to_int lo = 0 && to_int hi = 2
+
+let%test "hex" =
+  let x = of_hex_string "0xdeadbeef" in
+  to_hex_string x = "deadbeef" && num_bits x = 32
+
+let%test "to_int overflow" =
+  match to_int (shift_left (of_int 1) 62) with
+  | _ ->
+      false
+  | exception Failure _ ->
+      true
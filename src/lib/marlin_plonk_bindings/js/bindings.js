@@ -46,6 +46,58 @@ var caml_bigint_256_bytes_per_limb = plonk_wasm.caml_bigint_256_bytes_per_limb
 // Requires: plonk_wasm
 var caml_bigint_256_div = plonk_wasm.caml_bigint_256_div
 
+// Provides: caml_bigint_256_mod
+// Requires: plonk_wasm
+var caml_bigint_256_mod = plonk_wasm.caml_bigint_256_mod
+
+// Provides: caml_bigint_256_add
+// Requires: plonk_wasm, caml_js_to_bool
+var caml_bigint_256_add = function(x, y) {
+    var res = plonk_wasm.caml_bigint_256_add(x, y);
+    var carry = caml_js_to_bool(plonk_wasm.caml_bigint_256_add_carry(x, y));
+    return [0, res, carry];
+};
+
+// Provides: caml_bigint_256_sub
+// Requires: plonk_wasm, caml_js_to_bool
+var caml_bigint_256_sub = function(x, y) {
+    var res = plonk_wasm.caml_bigint_256_sub(x, y);
+    var borrow = caml_js_to_bool(plonk_wasm.caml_bigint_256_sub_borrow(x, y));
+    return [0, res, borrow];
+};
+
+// Provides: caml_bigint_256_mul
+// Requires: plonk_wasm
+var caml_bigint_256_mul = function(x, y) {
+    var lo = plonk_wasm.caml_bigint_256_mul_lo(x, y);
+    var hi = plonk_wasm.caml_bigint_256_mul_hi(x, y);
+    return [0, lo, hi];
+};
+
+// Provides: caml_bigint_256_shift_left
+// Requires: plonk_wasm
+var caml_bigint_256_shift_left = plonk_wasm.caml_bigint_256_shift_left
+
+// Provides: caml_bigint_256_shift_right
+// Requires: plonk_wasm
+var caml_bigint_256_shift_right = plonk_wasm.caml_bigint_256_shift_right
+
+// Provides: caml_bigint_256_logand
+// Requires: plonk_wasm
+var caml_bigint_256_logand = plonk_wasm.caml_bigint_256_logand
+
+// Provides: caml_bigint_256_logor
+// Requires: plonk_wasm
+var caml_bigint_256_logor = plonk_wasm.caml_bigint_256_logor
+
+// Provides: caml_bigint_256_logxor
+// Requires: plonk_wasm
+var caml_bigint_256_logxor = plonk_wasm.caml_bigint_256_logxor
+
+// Provides: caml_bigint_256_num_bits
+// Requires: plonk_wasm
+var caml_bigint_256_num_bits = plonk_wasm.caml_bigint_256_num_bits
+
 // Provides: caml_bigint_256_compare
 // Requires: plonk_wasm
 var caml_bigint_256_compare = plonk_wasm.caml_bigint_256_compare
@@ -60,6 +112,26 @@ var caml_bigint_256_to_string = function(x) {
     return caml_string_of_jsstring(plonk_wasm.caml_bigint_256_to_string(x));
 };
 
+// Provides: caml_bigint_256_of_hex_string
+// Requires: plonk_wasm, caml_jsstring_of_string
+var caml_bigint_256_of_hex_string = function (s) {
+    return plonk_wasm.caml_bigint_256_of_hex_string(caml_jsstring_of_string(s));
+};
+
+// Provides: caml_bigint_256_to_hex_string
+// Requires: plonk_wasm, caml_string_of_jsstring
+var caml_bigint_256_to_hex_string = function(x) {
+    return caml_string_of_jsstring(plonk_wasm.caml_bigint_256_to_hex_string(x));
+};
+
+// Provides: caml_bigint_256_of_int
+// Requires: plonk_wasm
+var caml_bigint_256_of_int = plonk_wasm.caml_bigint_256_of_int
+
+// Provides: caml_bigint_256_to_int
+// Requires: plonk_wasm
+var caml_bigint_256_to_int = plonk_wasm.caml_bigint_256_to_int
+
 // Provides: caml_bigint_256_test_bit
 // Requires: plonk_wasm, caml_js_to_bool
 var caml_bigint_256_test_bit = function(x, i) {
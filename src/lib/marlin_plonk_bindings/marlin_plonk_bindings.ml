@@ -8,6 +8,7 @@ module Bigint_256 = Marlin_plonk_bindings_bigint_256
 
 module Pasta_fp = Marlin_plonk_bindings_pasta_fp
 module Pasta_fq = Marlin_plonk_bindings_pasta_fq
+module Pasta_conversions = Marlin_plonk_bindings_pasta_conversions
 
 (* Finite field vectors *)
 
@@ -19,6 +20,10 @@ module Pasta_fq_vector = Marlin_plonk_bindings_pasta_fq_vector
 module Pasta_vesta = Marlin_plonk_bindings_pasta_vesta
 module Pasta_pallas = Marlin_plonk_bindings_pasta_pallas
 
+(* Deterministic RNGs *)
+
+module Pasta_rng = Marlin_plonk_bindings_pasta_rng
+
 (* URSs *)
 
 module Pasta_fp_urs = Marlin_plonk_bindings_pasta_fp_urs
@@ -0,0 +1,55 @@
+(* Conversions between the Pasta base and scalar fields. The Pallas scalar
+   field is the Vesta base field and vice versa, so recursive verification
+   needs to move values across. *)
+
+module Fp = Marlin_plonk_bindings_pasta_fp
+module Fq = Marlin_plonk_bindings_pasta_fq
+
+(** Raises if the value is not smaller than the target modulus. *)
+external fp_to_fq : Fp.t -> Fq.t = "caml_pasta_fp_to_fq"
+
+external fq_to_fp : Fq.t -> Fp.t = "caml_pasta_fq_to_fp"
+
+(** [fp_to_fq_split x] returns [(hi, lo)] with [x = 2 * hi + lo]. *)
+external fp_to_fq_split : Fp.t -> Fq.t * bool = "caml_pasta_fp_to_fq_split"
+
+external fq_to_fp_split : Fq.t -> Fp.t * bool = "caml_pasta_fq_to_fp_split"
+
+(** [fp_to_fq_limbs x ~limb_bits ~num_limbs] decomposes [x] into limbs, least
+    significant first. *)
+external fp_to_fq_limbs : Fp.t -> limb_bits:int -> num_limbs:int -> Fq.t array
+  = "caml_pasta_fp_to_fq_limbs"
+
+external fq_to_fp_limbs : Fq.t -> limb_bits:int -> num_limbs:int -> Fp.t array
+  = "caml_pasta_fq_to_fp_limbs"
+
+external fp_of_fq_limbs : Fq.t array -> limb_bits:int -> Fp.t
+  = "caml_pasta_fp_of_fq_limbs"
+
+external fq_of_fp_limbs : Fp.t array -> limb_bits:int -> Fq.t
+  = "caml_pasta_fq_of_fp_limbs"
+
+let%test "fp to fq" =
+  let x = Fp.random () in
+  Fp.to_string x = Fq.to_string (fp_to_fq x)
+
+let%test "split" =
+  let x = Fq.random () in
+  let hi, lo = fq_to_fp_split x in
+  let hi = Fq.of_string (Fp.to_string hi) in
+  let lo = if lo then Fq.of_int 1 else Fq.of_int 0 in
+  Fq.equal x (Fq.add (Fq.add hi hi) lo)
+
+let%test "limbs" =
+  let x = Fq.random () in
+  let limbs = fq_to_fp_limbs x ~limb_bits:88 ~num_limbs:3 in
+  Fq.equal x (fq_of_fp_limbs limbs ~limb_bits:88)
+
+let%test "limbs wider than the field" =
+  let raises f =
+    match f () with _ -> false | exception Invalid_argument _ -> true
+  in
+  let x = Fp.random () in
+  raises (fun () -> fp_of_fq_limbs [| Fq.of_int 1 |] ~limb_bits:max_int)
+  && raises (fun () -> fp_to_fq_limbs x ~limb_bits:255 ~num_limbs:1)
+  && raises (fun () -> fp_to_fq_limbs x ~limb_bits:64 ~num_limbs:max_int)
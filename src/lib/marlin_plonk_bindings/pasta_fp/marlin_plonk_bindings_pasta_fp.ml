@@ -23,6 +23,15 @@ external is_square : t -> bool = "caml_pasta_fp_is_square"
 
 external sqrt : t -> t option = "caml_pasta_fp_sqrt"
 
+(** [legendre x] is [0] if [x] is zero, [1] if it is a non-zero square and [-1]
+    otherwise. *)
+external legendre : t -> int = "caml_pasta_fp_legendre"
+
+external batch_sqrt : t array -> t option array = "caml_pasta_fp_batch_sqrt"
+
+external pow : t -> Marlin_plonk_bindings_bigint_256.t -> t
+  = "caml_pasta_fp_pow"
+
 external of_int : int -> t = "caml_pasta_fp_of_int"
 
 external to_string : t -> string = "caml_pasta_fp_to_string"
@@ -55,6 +64,17 @@ external to_bigint : t -> Marlin_plonk_bindings_bigint_256.t
 external of_bigint : Marlin_plonk_bindings_bigint_256.t -> t
   = "caml_pasta_fp_of_bigint"
 
+(** [to_bits x len] returns the [len] low bits of [x], least significant first.
+    Raises if [x] does not fit in [len] bits. *)
+external to_bits : t -> int -> bool array = "caml_pasta_fp_to_bits"
+
+external of_bits : bool array -> t = "caml_pasta_fp_of_bits"
+
+external multiplicative_generator : unit -> t
+  = "caml_pasta_fp_multiplicative_generator"
+
+external two_adicity : unit -> int = "caml_pasta_fp_two_adicity"
+
 external two_adic_root_of_unity : unit -> t
   = "caml_pasta_fp_two_adic_root_of_unity"
 
@@ -66,6 +86,12 @@ external of_bytes : Bytes.t -> t = "caml_pasta_fp_of_bytes"
 
 external deep_copy : t -> t = "caml_pasta_fp_deep_copy"
 
+(** [b_poly_evaluations chals points] evaluates the [b] polynomial of each set
+    of challenges, [prod_i (1 + chals.(i) x^(2^(k - 1 - i)))], at each of the
+    points, without computing its coefficients. *)
+external b_poly_evaluations : t array array -> t array -> t array array
+  = "caml_pasta_fp_b_poly_evaluations"
+
 let%test "deep_copy" =
   let x = random () in
   deep_copy x = x
@@ -76,3 +102,36 @@ let%test "operations" =
   let three = div six two in
   let six' = add three three in
   compare six six' = 0
+
+let%test "pow" =
+  let x = of_int 3 in
+  let e = Marlin_plonk_bindings_bigint_256.of_decimal_string "4" in
+  equal (pow x e) (of_int 81)
+
+let%test "bits" =
+  let x = random () in
+  let bits = to_bits x (size_in_bits ()) in
+  equal x (of_bits bits) && to_bits (of_int 6) 3 = [| false; true; true |]
+
+let%test "legendre" =
+  let x = random () in
+  legendre (of_int 0) = 0
+  && legendre (square x) = (if equal x (of_int 0) then 0 else 1)
+  && legendre (multiplicative_generator ()) = -1
+
+let%test "b_poly_evaluations" =
+  let a = random () in
+  let b = random () in
+  let x = random () in
+  let y = random () in
+  let expected x =
+    mul (add (of_int 1) (mul a (square x))) (add (of_int 1) (mul b x))
+  in
+  match b_poly_evaluations [| [| a; b |]; [||] |] [| x; y |] with
+  | [| [| bx; by |]; [| one_x; one_y |] |] ->
+      equal bx (expected x)
+      && equal by (expected y)
+      && equal one_x (of_int 1)
+      && equal one_y (of_int 1)
+  | _ ->
+      false
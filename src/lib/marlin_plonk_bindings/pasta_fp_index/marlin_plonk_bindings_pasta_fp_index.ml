@@ -14,6 +14,86 @@ module Gate_vector = struct
   external wrap : t -> Plonk_gate.Wire.t -> Plonk_gate.Wire.t -> unit
     = "caml_pasta_fp_plonk_gate_vector_wrap"
 
+  (** [connect t a b] declares that the wires [a] and [b] hold the same
+      variable. The permutation cycles are computed from these declarations
+      when the index is created, and take precedence over [wrap]. *)
+  external connect : t -> Plonk_gate.Wire.t -> Plonk_gate.Wire.t -> unit
+    = "caml_pasta_fp_plonk_gate_vector_connect"
+
+  (** Rewires the gates according to the wires connected so far. This is only
+      needed to inspect the wiring before the index is created. Raises
+      [Failure] if a connected wire is beyond the last gate.
+
+      The gates of [t] are modified in place, and [get] returns the rewired
+      gates afterwards. The queries below that work on the wiring of the
+      circuit ([check_witness], [write_witness_csv], [stats], [digest],
+      [diff], [write_json] and [write_dot]) call it first, so they modify [t]
+      in the same way. *)
+  external apply_wiring : t -> unit
+    = "caml_pasta_fp_plonk_gate_vector_apply_wiring"
+
+  (** [check_witness t ~public witness ~limit] checks the witness, laid out as
+      for [Marlin_plonk_bindings_pasta_fp_proof.create], against the gates and
+      their wiring. It reports the first [limit] failing rows, whose gate
+      constraint does not hold or whose wires differ from the wires they are
+      connected to. Raises [Invalid_argument] if there is no domain for the
+      circuit. Rewires [t] as [apply_wiring] does. *)
+  external check_witness :
+       t
+    -> public:int
+    -> Marlin_plonk_bindings_pasta_fp_vector.t
+    -> limit:int
+    -> Marlin_plonk_bindings_pasta_fp.t Plonk_witness_check.t
+    = "caml_pasta_fp_plonk_gate_vector_check_witness"
+
+  (** [write_witness_csv t witness ~first ~last path] writes the gates and
+      witness values of the rows [first] to [last] to [path] as CSV. Rewires
+      [t] as [apply_wiring] does. *)
+  external write_witness_csv :
+       t
+    -> Marlin_plonk_bindings_pasta_fp_vector.t
+    -> first:int
+    -> last:int
+    -> string
+    -> unit = "caml_pasta_fp_plonk_gate_vector_write_witness_csv"
+
+  (** [stats t ~public urs] reports the shape of the circuit as [create]
+      would build it with [public] public inputs, and compares the depth of
+      [urs], if given, with the depth the circuit needs. Rewires [t] as
+      [apply_wiring] does. *)
+  external stats :
+       t
+    -> public:int
+    -> Marlin_plonk_bindings_pasta_fp_urs.t option
+    -> Plonk_circuit_stats.t = "caml_pasta_fp_plonk_gate_vector_stats"
+
+  (** [digest t ~public] is a digest of the gates, their wiring, the number of
+      public inputs and the size of the domain. It is equal to the [digest] of
+      the index that [create] builds from [t] with [public] public inputs.
+      Rewires [t] as [apply_wiring] does. *)
+  external digest : t -> public:int -> Bytes.t
+    = "caml_pasta_fp_plonk_gate_vector_digest"
+
+  (** [diff before after] compares the gates of two circuits row by row,
+      after rewiring both as [apply_wiring] does. *)
+  external diff :
+       t
+    -> t
+    -> Marlin_plonk_bindings_pasta_fp.t Plonk_circuit_diff.t
+    = "caml_pasta_fp_plonk_gate_vector_diff"
+
+  (** [write_json ?rows t path] writes the gates in [rows], or all of them, to
+      [path] as a JSON array, with their coefficients as decimal strings.
+      Rewires [t] as [apply_wiring] does. *)
+  external write_json : ?rows:Plonk_row_selection.t -> t -> string -> unit
+    = "caml_pasta_fp_plonk_gate_vector_write_json"
+
+  (** [write_dot ?rows t path] writes the gates in [rows], or all of them, to
+      [path] as a Graphviz DOT graph, with an edge for each wire between two of
+      these rows. Rewires [t] as [apply_wiring] does. *)
+  external write_dot : ?rows:Plonk_row_selection.t -> t -> string -> unit
+    = "caml_pasta_fp_plonk_gate_vector_write_dot"
+
   let%test "gate vector" =
     let vec = create () in
     let l : Plonk_gate.Wire.t = { row = 1; col = L } in
@@ -29,6 +109,235 @@ module Gate_vector = struct
     wrap vec t h ;
     let z = get vec 0 in
     z.wires.l.row = 8 && z.wires.l.col = O
+
+  let%test "connect" =
+    let vec = create () in
+    let wire row col : Plonk_gate.Wire.t = { row; col } in
+    for row = 0 to 3 do
+      let wires : Plonk_gate.Wires.t =
+        { row; l = wire row L; r = wire row R; o = wire row O }
+      in
+      let gate : _ Plonk_gate.t = { kind = Zero; wires; c = [||] } in
+      add vec gate
+    done ;
+    connect vec (wire 0 O) (wire 2 L) ;
+    connect vec (wire 3 R) (wire 2 L) ;
+    apply_wiring vec ;
+    let g0 = get vec 0 in
+    let g1 = get vec 1 in
+    let g2 = get vec 2 in
+    let g3 = get vec 3 in
+    let out_of_range =
+      connect vec (wire 4 L) (wire 0 L) ;
+      match apply_wiring vec with () -> false | exception Failure _ -> true
+    in
+    let bad_get = match get vec 4 with _ -> false | exception _ -> true in
+    (* The cycle visits the positions in order: (0, O) -> (2, L) -> (3, R). *)
+    g0.wires.o = wire 2 L
+    && g2.wires.l = wire 3 R
+    && g3.wires.r = wire 0 O
+    && g1.wires.l = wire 1 L
+    && out_of_range && bad_get
+
+  let%test "check_witness" =
+    let module Fp = Marlin_plonk_bindings_pasta_fp in
+    let module Vector = Marlin_plonk_bindings_pasta_fp_vector in
+    let vec = create () in
+    let wire row col : Plonk_gate.Wire.t = { row; col } in
+    (* Two gates [l + r = o], with the output of the first fed to the second. *)
+    let c =
+      Fp.[| of_int 1; of_int 1; negate (of_int 1); of_int 0; of_int 0 |]
+    in
+    for row = 0 to 1 do
+      let wires : Plonk_gate.Wires.t =
+        { row; l = wire row L; r = wire row R; o = wire row O }
+      in
+      let gate : _ Plonk_gate.t = { kind = Generic; wires; c } in
+      add vec gate
+    done ;
+    connect vec (wire 0 O) (wire 1 L) ;
+    let witness values =
+      let v = Vector.create () in
+      Array.iter (fun x -> Vector.emplace_back v (Fp.of_int x)) values ;
+      v
+    in
+    (* The columns [L], [R] and [O] in turn, over a domain of size 2. *)
+    let good = witness [| 1; 3; 2; 4; 3; 7 |] in
+    let bad = witness [| 1; 3; 2; 4; 3; 8 |] in
+    let good_report = check_witness vec ~public:0 good ~limit:10 in
+    let bad_report = check_witness vec ~public:0 bad ~limit:10 in
+    let path = Filename.temp_file "witness" ".csv" in
+    write_witness_csv vec bad ~first:0 ~last:1 path ;
+    let lines =
+      let ic = open_in path in
+      let rec count n =
+        match input_line ic with
+        | _ ->
+            count (n + 1)
+        | exception End_of_file ->
+            close_in ic ; n
+      in
+      count 0
+    in
+    Sys.remove path ;
+    good_report.num_failures = 0
+    && bad_report.num_failures = 1
+    && bad_report.failures.(0).row = 1
+    && bad_report.failures.(0).copies = [||]
+    && (not bad_report.failures.(0).holds)
+    && ( match bad_report.failures.(0).residual with
+       | Some r ->
+           Fp.equal r (Fp.negate (Fp.of_int 1))
+       | None ->
+           false )
+    (* A header and two rows. *)
+    && lines = 3
+
+  let%test "check_witness of a poseidon gate" =
+    let module Fp = Marlin_plonk_bindings_pasta_fp in
+    let module Vector = Marlin_plonk_bindings_pasta_fp_vector in
+    let vec = create () in
+    let wire row col : Plonk_gate.Wire.t = { row; col } in
+    (* A round with zero round constants, whose output is the next row. *)
+    for row = 0 to 1 do
+      let wires : Plonk_gate.Wires.t =
+        { row; l = wire row L; r = wire row R; o = wire row O }
+      in
+      let kind : Plonk_gate.Kind.t = if row = 0 then Poseidon else Zero in
+      let c = if row = 0 then Array.init 3 (fun _ -> Fp.of_int 0) else [||] in
+      let gate : _ Plonk_gate.t = { kind; wires; c } in
+      add vec gate
+    done ;
+    let witness values =
+      let v = Vector.create () in
+      Array.iter (fun x -> Vector.emplace_back v (Fp.of_int x)) values ;
+      v
+    in
+    (* The round maps zero to zero, but not one. *)
+    let good =
+      check_witness vec ~public:0 (witness (Array.make 6 0)) ~limit:1
+    in
+    let bad =
+      check_witness vec ~public:0 (witness [| 1; 0; 0; 0; 0; 0 |]) ~limit:1
+    in
+    good.num_failures = 0
+    && bad.num_failures = 1
+    && bad.failures.(0).row = 0
+    && (not bad.failures.(0).holds)
+    && bad.failures.(0).residual = None
+
+  let%test "stats" =
+    let vec = create () in
+    let wire row col : Plonk_gate.Wire.t = { row; col } in
+    for row = 0 to 4 do
+      let wires : Plonk_gate.Wires.t =
+        { row; l = wire row L; r = wire row R; o = wire row O }
+      in
+      let gate : _ Plonk_gate.t =
+        { kind = (if row = 0 then Zero else Generic); wires; c = [||] }
+      in
+      add vec gate
+    done ;
+    connect vec (wire 0 O) (wire 1 L) ;
+    connect vec (wire 1 O) (wire 2 L) ;
+    connect vec (wire 2 O) (wire 3 L) ;
+    connect vec (wire 3 R) (wire 4 R) ;
+    let stats = stats vec ~public:1 None in
+    stats.rows = 5
+    && stats.gate_counts = Plonk_gate.Kind.[| (Zero, 1); (Generic, 4) |]
+    && stats.domain_d1_size = 8
+    && stats.domain_d8_size = 64
+    && stats.headroom = 3
+    && stats.cycles = 4
+    && stats.cycle_lengths = [| (2, 4) |]
+    && stats.broken_wires = 0
+    && stats.urs_depth = None
+
+  let%test "diff" =
+    let module Fp = Marlin_plonk_bindings_pasta_fp in
+    let wire row col : Plonk_gate.Wire.t = { row; col } in
+    let gates ~rows ~coeff =
+      let vec = create () in
+      for row = 0 to rows - 1 do
+        let wires : Plonk_gate.Wires.t =
+          { row; l = wire row L; r = wire row R; o = wire row O }
+        in
+        let c = Fp.[| of_int 1; of_int (if row = 1 then coeff else 1) |] in
+        let gate : _ Plonk_gate.t = { kind = Generic; wires; c } in
+        add vec gate
+      done ;
+      vec
+    in
+    let before = gates ~rows:3 ~coeff:1 in
+    let after = gates ~rows:4 ~coeff:2 in
+    connect after (wire 0 O) (wire 2 L) ;
+    let same = diff before (gates ~rows:3 ~coeff:1) in
+    let d = diff before after in
+    let changes =
+      Array.map
+        (fun (row : _ Plonk_circuit_diff.Row.t) ->
+          (row.row, row.change, row.coefficients, row.rewired))
+        d.gates
+    in
+    same.gates = [||]
+    && d.rows = (3, 4)
+    && changes
+       = Plonk_circuit_diff.Change.
+           [| (0, Modified, [||], [| Plonk_gate.Col.O |])
+            ; (1, Modified, [| 1 |], [||])
+            ; (2, Modified, [||], [| Plonk_gate.Col.L |])
+            ; (3, Added, [||], [||])
+           |]
+
+  let%test "write_json and write_dot" =
+    let vec = create () in
+    let wire row col : Plonk_gate.Wire.t = { row; col } in
+    for row = 0 to 3 do
+      let wires : Plonk_gate.Wires.t =
+        { row; l = wire row L; r = wire row R; o = wire row O }
+      in
+      let gate : _ Plonk_gate.t = { kind = Generic; wires; c = [||] } in
+      add vec gate
+    done ;
+    connect vec (wire 0 O) (wire 1 L) ;
+    connect vec (wire 1 O) (wire 2 L) ;
+    let lines path =
+      let ic = open_in path in
+      let rec go acc =
+        match input_line ic with
+        | line ->
+            go (line :: acc)
+        | exception End_of_file ->
+            close_in ic ; List.rev acc
+      in
+      let lines = go [] in
+      Sys.remove path ; lines
+    in
+    let is_edge line =
+      let rec go i =
+        i + 1 < String.length line
+        && ((line.[i] = '-' && line.[i + 1] = '>') || go (i + 1))
+      in
+      go 0
+    in
+    let json = Filename.temp_file "gates" ".json" in
+    write_json ~rows:(Range (1, 2)) vec json ;
+    let dot = Filename.temp_file "gates" ".dot" in
+    write_dot ~rows:(Neighbourhood (0, 1)) vec dot ;
+    let edges = List.filter is_edge (lines dot) in
+    let invalid rows =
+      match write_dot ~rows vec dot with
+      | () ->
+          false
+      | exception Invalid_argument _ ->
+          true
+    in
+    (* An opening and a closing bracket, and a line for each gate. *)
+    List.length (lines json) = 4
+    (* Rows 0 and 1 are wired to each other in both directions. *)
+    && List.length edges = 2
+    && invalid (Range (-1, 0))
+    && invalid (Range (4, 5))
 end
 
 type t
@@ -47,9 +356,77 @@ external domain_d4_size : t -> int = "caml_pasta_fp_plonk_index_domain_d4_size"
 
 external domain_d8_size : t -> int = "caml_pasta_fp_plonk_index_domain_d8_size"
 
+(** The shape of the circuit of the index, as [Gate_vector.stats] reports it
+    for the gate vector the index was created from. *)
+external stats : t -> Plonk_circuit_stats.t = "caml_pasta_fp_plonk_index_stats"
+
+external digest : t -> Bytes.t = "caml_pasta_fp_plonk_index_digest"
+
+(** [diff before after] compares the circuits of two indices row by row, and
+    their numbers of public inputs. *)
+external diff : t -> t -> Marlin_plonk_bindings_pasta_fp.t Plonk_circuit_diff.t
+  = "caml_pasta_fp_plonk_index_diff"
+
+(** [read ?offset ?digest urs path] reads an index written by [write]. If
+    [digest] is given, raises [Failure] unless the index was built for the
+    circuit with that digest. *)
 external read :
-  ?offset:int -> Marlin_plonk_bindings_pasta_fp_urs.t -> string -> t
-  = "caml_pasta_fp_plonk_index_read"
+     ?offset:int
+  -> ?digest:Bytes.t
+  -> Marlin_plonk_bindings_pasta_fp_urs.t
+  -> string
+  -> t = "caml_pasta_fp_plonk_index_read"
 
 external write : ?append:bool -> t -> string -> unit
   = "caml_pasta_fp_plonk_index_write"
+
+let%test "digest" =
+  let module Fp = Marlin_plonk_bindings_pasta_fp in
+  let gates ?(padding = 0) coeff =
+    let vec = Gate_vector.create () in
+    let wire row col : Plonk_gate.Wire.t = { row; col } in
+    for row = 0 to 2 + padding do
+      let wires : Plonk_gate.Wires.t =
+        { row; l = wire row L; r = wire row R; o = wire row O }
+      in
+      let gate : _ Plonk_gate.t =
+        if row > 2 then { kind = Zero; wires; c = [||] }
+        else
+          let c =
+            Fp.[| of_int coeff; of_int 1; of_int 0; of_int 0; of_int 0 |]
+          in
+          { kind = Generic; wires; c }
+      in
+      Gate_vector.add vec gate
+    done ;
+    Gate_vector.connect vec (wire 0 O) (wire 1 L) ;
+    vec
+  in
+  let vec = gates 1 in
+  let d = Gate_vector.digest vec ~public:1 in
+  let index = create vec 1 (Marlin_plonk_bindings_pasta_fp_urs.create 32) in
+  Bytes.equal d (Gate_vector.digest (gates 1) ~public:1)
+  && (not (Bytes.equal d (Gate_vector.digest (gates 2) ~public:1)))
+  && (not (Bytes.equal d (Gate_vector.digest vec ~public:0)))
+  && Bytes.equal d (Gate_vector.digest (gates ~padding:1 1) ~public:1)
+  && (not (Bytes.equal d (Gate_vector.digest (gates ~padding:2 1) ~public:1)))
+  && Bytes.equal d (digest index)
+
+let%test "stats" =
+  let module Fp = Marlin_plonk_bindings_pasta_fp in
+  let urs = Marlin_plonk_bindings_pasta_fp_urs.create 32 in
+  let vec = Gate_vector.create () in
+  let wire row col : Plonk_gate.Wire.t = { row; col } in
+  for row = 0 to 2 do
+    let wires : Plonk_gate.Wires.t =
+      { row; l = wire row L; r = wire row R; o = wire row O }
+    in
+    let c = Fp.[| of_int 1; of_int 1; of_int 0; of_int 0; of_int 0 |] in
+    let gate : _ Plonk_gate.t = { kind = Generic; wires; c } in
+    Gate_vector.add vec gate
+  done ;
+  Gate_vector.connect vec (wire 0 O) (wire 1 L) ;
+  let expected = Gate_vector.stats vec ~public:1 (Some urs) in
+  (* The index pads the 3 gates to the 4 rows of its domain. *)
+  let stats = stats (create vec 1 urs) in
+  stats = expected && stats.rows = 3 && stats.headroom = 1
@@ -24,6 +24,17 @@ external dummy : unit -> t = "caml_pasta_fp_plonk_verifier_index_dummy"
 
 external deep_copy : t -> t = "caml_pasta_fp_plonk_verifier_index_deep_copy"
 
+(** [diff before after] is the names of the fields of [before] and [after]
+    that differ, such as ["ql_comm"] or ["shifts"]. The URSs are not
+    compared. *)
+external diff : t -> t -> string array
+  = "caml_pasta_fp_plonk_verifier_index_diff"
+
 let%test "deep_copy" =
   let x = dummy () in
   deep_copy x = x
+
+let%test "diff" =
+  let x = dummy () in
+  let y = { x with max_poly_size = x.max_poly_size + 1 } in
+  diff x (deep_copy x) = [||] && diff x y = [| "max_poly_size" |]
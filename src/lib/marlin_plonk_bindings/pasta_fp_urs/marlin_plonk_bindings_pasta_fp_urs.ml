@@ -8,13 +8,100 @@ end
 
 external create : int -> t = "caml_pasta_fp_urs_create"
 
+external depth : t -> int = "caml_pasta_fp_urs_depth"
+
+(** [extend urs depth] is a URS of the given depth which shares its first
+    [depth urs] points with [urs]. Only the missing points are derived.
+    Commitments to polynomials that fit in [urs] are unchanged. Raises
+    [Failure] if [urs] was not derived by [create]. *)
+external extend : t -> int -> t = "caml_pasta_fp_urs_extend"
+
+(** [trim urs depth] is the URS made of the first [depth] points of [urs]. *)
+external trim : t -> int -> t = "caml_pasta_fp_urs_trim"
+
+(** A digest of the points of the URS. Two URSs are the same exactly when
+    their digests are. *)
+external digest : t -> Bytes.t = "caml_pasta_fp_urs_digest"
+
+(** Checks that the points of the URS are the ones [create] derives. With
+    [sample_size], only that many randomly chosen points are checked. Raises
+    [Failure] if the derivation no longer matches the one [create] uses. *)
+external verify_derivation : ?sample_size:int -> t -> bool
+  = "caml_pasta_fp_urs_verify_derivation"
+
 external write : ?append:bool -> t -> string -> unit = "caml_pasta_fp_urs_write"
 
 external read : ?offset:int -> string -> t option = "caml_pasta_fp_urs_read"
 
+(** Atomically writes the URS to the given path, in a versioned container
+    format with a checksum. *)
+external save : t -> string -> unit = "caml_pasta_fp_urs_save"
+
+(** Reads a URS written by [save]. Raises [Failure] describing the problem if
+    the file is for the other curve, truncated, corrupted or followed by extra
+    bytes. *)
+external load : string -> t = "caml_pasta_fp_urs_load"
+
+(** Writes the URS in a fixed-stride layout that can be memory-mapped with
+    [Mapped.create]. *)
+external save_mapped : t -> string -> unit = "caml_pasta_fp_urs_save_mapped"
+
+(** A memory-mapped URS file. Points are decoded and checked when they are
+    used, and the file's pages are shared between processes. Every point is
+    also checked on a background thread, whose result is given by [checked]
+    and [wait_checked]. *)
+module Mapped = struct
+  type nonrec urs = t
+
+  type t
+
+  external create : string -> t = "caml_pasta_fp_urs_map"
+
+  external depth : t -> int = "caml_pasta_fp_mapped_urs_depth"
+
+  external g : t -> int -> Marlin_plonk_bindings_pasta_vesta.Affine.t
+    = "caml_pasta_fp_mapped_urs_g"
+
+  external h : t -> Marlin_plonk_bindings_pasta_vesta.Affine.t
+    = "caml_pasta_fp_mapped_urs_h"
+
+  (** Whether every point is valid, or [None] while the points are still
+      being checked. *)
+  external checked : t -> bool option = "caml_pasta_fp_mapped_urs_checked"
+
+  (** Waits until every point has been checked. Raises [Failure] describing
+      the first invalid point, if any. *)
+  external wait_checked : t -> unit = "caml_pasta_fp_mapped_urs_wait_checked"
+
+  (** [commit t coeffs] is the commitment to the polynomial with coefficients
+      [coeffs], as [commit] gives it without a degree bound. Only the first
+      [Array.length coeffs] points are decoded. *)
+  external commit :
+    t -> Marlin_plonk_bindings_pasta_fp.t array -> Poly_comm.t
+    = "caml_pasta_fp_mapped_urs_commit"
+
+  (** Decodes all of the points, in parallel, for example to create an index. *)
+  external to_urs : t -> urs = "caml_pasta_fp_mapped_urs_to_urs"
+end
+
 external lagrange_commitment : t -> domain_size:int -> int -> Poly_comm.t
   = "caml_pasta_fp_urs_lagrange_commitment"
 
+(** Computes the commitments to every Lagrange basis polynomial of the domain
+    at once, and caches them in the URS for [lagrange_commitment]. *)
+external lagrange_commitments : t -> domain_size:int -> Poly_comm.t array
+  = "caml_pasta_fp_urs_lagrange_commitments"
+
+(** Persists the cached Lagrange basis commitments, for example next to the
+    URS file. *)
+external write_lagrange_bases : t -> string -> unit
+  = "caml_pasta_fp_urs_write_lagrange_bases"
+
+(** Restores the commitments written by [write_lagrange_bases] into the cache.
+    Raises [Failure] if they were computed for a different URS. *)
+external read_lagrange_bases : t -> string -> unit
+  = "caml_pasta_fp_urs_read_lagrange_bases"
+
 external commit_evaluations :
   t -> domain_size:int -> Marlin_plonk_bindings_pasta_fp.t array -> Poly_comm.t
   = "caml_pasta_fp_urs_commit_evaluations"
@@ -23,14 +110,36 @@ external b_poly_commitment :
   t -> Marlin_plonk_bindings_pasta_fp.t array -> Poly_comm.t
   = "caml_pasta_fp_urs_b_poly_commitment"
 
+(** Commits to the [b] polynomial of each set of challenges, in parallel. *)
+external b_poly_commitments :
+  t -> Marlin_plonk_bindings_pasta_fp.t array array -> Poly_comm.t array
+  = "caml_pasta_fp_urs_b_poly_commitments"
+
+(** Checks the accumulators against their challenges, concatenated. [rounds]
+    gives the number of challenges of each accumulator, which by default all
+    have the same number. Raises [Failure] if the challenges don't match.
+    The random combination is drawn from [rng] if given. *)
 external batch_accumulator_check :
-     t
+     ?rng:Marlin_plonk_bindings_pasta_rng.t
+  -> ?rounds:int array
+  -> t
   -> Marlin_plonk_bindings_pasta_vesta.Affine.t array
   -> Marlin_plonk_bindings_pasta_fp.t array
   -> bool = "caml_pasta_fp_urs_batch_accumulator_check"
 
+(** As [batch_accumulator_check], but returns the indices of the accumulators
+    that fail, in increasing order, by bisecting the batch. *)
+external batch_accumulator_failures :
+     ?rng:Marlin_plonk_bindings_pasta_rng.t
+  -> ?rounds:int array
+  -> t
+  -> Marlin_plonk_bindings_pasta_vesta.Affine.t array
+  -> Marlin_plonk_bindings_pasta_fp.t array
+  -> int array = "caml_pasta_fp_urs_batch_accumulator_failures"
+
 external batch_accumulator_generate :
-     t
+     ?rounds:int array
+  -> t
   -> int
   -> Marlin_plonk_bindings_pasta_fp.t array
   -> Marlin_plonk_bindings_pasta_vesta.Affine.t array
@@ -38,3 +147,244 @@ external batch_accumulator_generate :
 
 external h : t -> Marlin_plonk_bindings_pasta_vesta.Affine.t
   = "caml_pasta_fp_urs_h"
+
+module Opening_proof = struct
+  type t =
+    ( Marlin_plonk_bindings_pasta_fp.t
+    , Marlin_plonk_bindings_pasta_vesta.Affine.t )
+    Marlin_plonk_bindings_types.Plonk_proof.Opening_proof.t
+end
+
+(** The blinding factors of the parts of a hiding commitment. *)
+module Blinders = struct
+  type t =
+    Marlin_plonk_bindings_pasta_fp.t Marlin_plonk_bindings_types.Poly_comm.t
+end
+
+(** Commits to the polynomial with the given coefficients, shifted for the
+    degree bound [max_degree] if given. *)
+external commit :
+     t
+  -> Marlin_plonk_bindings_pasta_fp.t array
+  -> max_degree:int option
+  -> Poly_comm.t
+  = "caml_pasta_fp_poly_comm_commit"
+
+(** As [commit], but blinds each part of the commitment with [r h] for a fresh
+    random [r]. Returns the commitment with the blinding factors of its parts,
+    which [open_] takes to open it. *)
+external commit_hiding :
+     t
+  -> Marlin_plonk_bindings_pasta_fp.t array
+  -> max_degree:int option
+  -> Poly_comm.t * Blinders.t
+  = "caml_pasta_fp_poly_comm_commit_hiding"
+
+(** The evaluations of each chunk of the polynomial, as expected by
+    [verify_opening]. *)
+external evaluate :
+     t
+  -> Marlin_plonk_bindings_pasta_fp.t array
+  -> Marlin_plonk_bindings_pasta_fp.t
+  -> Marlin_plonk_bindings_pasta_fp.t array
+  = "caml_pasta_fp_poly_comm_evaluate"
+
+(** Creates an opening proof of the polynomials, with their degree bounds, at
+    all of the points. Each polynomial comes with the blinding factors returned
+    by [commit_hiding], or [None] if it was committed to with [commit]. The pair
+    holds the [polyscale] and [evalscale] challenges used to combine the
+    polynomials and the points. *)
+external open_ :
+     t
+  -> ( Marlin_plonk_bindings_pasta_fp.t array
+     * int option
+     * Blinders.t option )
+     array
+  -> Marlin_plonk_bindings_pasta_fp.t array
+  -> Marlin_plonk_bindings_pasta_fp.t * Marlin_plonk_bindings_pasta_fp.t
+  -> Opening_proof.t = "caml_pasta_fp_poly_comm_open"
+
+(** Verifies a proof made by [open_]. Each commitment comes with its degree
+    bound and its chunked evaluations at each of the points. *)
+external verify_opening :
+     t
+  -> ( Poly_comm.t
+     * int option
+     * Marlin_plonk_bindings_pasta_fp.t array array )
+     array
+  -> Marlin_plonk_bindings_pasta_fp.t array
+  -> Marlin_plonk_bindings_pasta_fp.t * Marlin_plonk_bindings_pasta_fp.t
+  -> Opening_proof.t
+  -> bool = "caml_pasta_fp_poly_comm_verify"
+
+let%test "save and load" =
+  let path = Filename.temp_file "urs" ".bin" in
+  let urs = create 16 in
+  save urs path ;
+  let loaded = load path in
+  let byte =
+    let ic = open_in_bin path in
+    seek_in ic 100 ;
+    let byte = input_byte ic in
+    close_in ic ; byte
+  in
+  let oc = open_out_gen [ Open_wronly; Open_binary ] 0o644 path in
+  seek_out oc 100 ;
+  output_byte oc (byte lxor 1) ;
+  close_out oc ;
+  let corrupted =
+    match load path with _ -> false | exception Failure _ -> true
+  in
+  save urs path ;
+  let oc = open_out_gen [ Open_append; Open_binary ] 0o644 path in
+  output_byte oc 0 ;
+  close_out oc ;
+  let extended =
+    match load path with _ -> false | exception Failure _ -> true
+  in
+  Sys.remove path ;
+  h loaded = h urs && corrupted && extended
+
+let%test "save_mapped and map" =
+  let path = Filename.temp_file "urs" ".mapped" in
+  let urs = create 16 in
+  save_mapped urs path ;
+  let mapped = Mapped.create path in
+  let coeffs =
+    Array.init 5 (fun i -> Marlin_plonk_bindings_pasta_fp.of_int i)
+  in
+  let committed = Mapped.commit mapped coeffs in
+  Mapped.wait_checked mapped ;
+  let loaded = Mapped.to_urs mapped in
+  Sys.remove path ;
+  Mapped.depth mapped = 16
+  && Mapped.checked mapped = Some true
+  && committed = commit urs coeffs ~max_degree:None
+  && Mapped.h mapped = h urs
+  && h loaded = h urs
+  && Mapped.g mapped 3 = Mapped.g mapped 3
+
+let%test "lagrange_commitments" =
+  let urs = create 16 in
+  let expected = Array.init 32 (lagrange_commitment urs ~domain_size:32) in
+  let path = Filename.temp_file "urs" ".lagrange" in
+  let computed = lagrange_commitments urs ~domain_size:32 in
+  write_lagrange_bases urs path ;
+  (* [create] is deterministic, so a fresh URS of the same depth is the same
+     URS. *)
+  let restored = create 16 in
+  read_lagrange_bases restored path ;
+  let rejected =
+    match read_lagrange_bases (create 8) path with
+    | () ->
+        false
+    | exception Failure _ ->
+        (* A URS of another depth is a different URS, so its bases can't be
+           restored. *)
+        true
+  in
+  Sys.remove path ;
+  computed = expected
+  && Array.init 32 (lagrange_commitment restored ~domain_size:32) = expected
+  && rejected
+
+let%test "extend and trim" =
+  let small = create 8 in
+  let large = create 16 in
+  let extended = extend small 16 in
+  let trimmed = trim large 8 in
+  depth extended = 16
+  && depth trimmed = 8
+  && lagrange_commitment extended ~domain_size:16 3
+     = lagrange_commitment large ~domain_size:16 3
+  && lagrange_commitment trimmed ~domain_size:8 5
+     = lagrange_commitment small ~domain_size:8 5
+
+let%test "extend derives the points of create" =
+  (* Extending the empty URS derives every point, which pins the derivation
+     to the one [create] uses. *)
+  List.for_all
+    (fun d -> Bytes.equal (digest (extend (create 0) d)) (digest (create d)))
+    [ 1; 2; 3; 7; 16; 33 ]
+
+let%test "digest and verify_derivation" =
+  let urs = create 16 in
+  let extended = extend (create 8) 16 in
+  Bytes.equal (digest urs) (digest extended)
+  && (not (Bytes.equal (digest urs) (digest (create 8))))
+  && verify_derivation urs
+  && verify_derivation ~sample_size:4 extended
+
+let%test "commit, open and verify" =
+  let module Fp = Marlin_plonk_bindings_pasta_fp in
+  let urs = create 16 in
+  (* Longer than the URS, so that it is committed to in two chunks. *)
+  let p = Array.init 24 (fun i -> Fp.of_int (i + 1)) in
+  let q = Array.init 5 (fun i -> Fp.of_int (3 * i)) in
+  let comm_p = commit urs p ~max_degree:None in
+  let comm_q = commit urs q ~max_degree:(Some 8) in
+  let hiding, blinders = commit_hiding urs p ~max_degree:None in
+  let ({ unshifted; shifted } : Blinders.t) = blinders in
+  let points = [| Fp.random (); Fp.random () |] in
+  let scales = (Fp.random (), Fp.random ()) in
+  let proof =
+    open_ urs [| (p, None, None); (q, Some 8, None) |] points scales
+  in
+  let hiding_proof =
+    open_ urs [| (p, None, Some blinders); (q, Some 8, None) |] points scales
+  in
+  let evals poly = Array.map (evaluate urs poly) points in
+  let wrong = Array.map (Array.map (Fp.add (Fp.of_int 1))) (evals q) in
+  Array.length unshifted = 2
+  && shifted = None
+  && hiding <> comm_p
+  && verify_opening urs
+       [| (comm_p, None, evals p); (comm_q, Some 8, evals q) |]
+       points scales proof
+  && not
+       (verify_opening urs
+          [| (comm_p, None, evals p); (comm_q, Some 8, wrong) |]
+          points scales proof)
+  && verify_opening urs
+       [| (hiding, None, evals p); (comm_q, Some 8, evals q) |]
+       points scales hiding_proof
+  && not
+       (verify_opening urs
+          [| (comm_p, None, evals p); (comm_q, Some 8, evals q) |]
+          points scales hiding_proof)
+
+let%test "batch accumulators with different numbers of rounds" =
+  let module Fp = Marlin_plonk_bindings_pasta_fp in
+  let urs = create 16 in
+  let chals = Array.init 7 (fun _ -> Fp.random ()) in
+  let rounds = [| 4; 3 |] in
+  let comms = batch_accumulator_generate ~rounds urs 2 chals in
+  let raises f = match f () with _ -> false | exception Failure _ -> true in
+  batch_accumulator_check ~rounds urs comms chals
+  && (not
+        (batch_accumulator_check ~rounds urs [| comms.(1); comms.(0) |] chals))
+  (* 7 challenges can't be split evenly between 2 accumulators. *)
+  && raises (fun () -> batch_accumulator_check urs comms chals)
+  (* 5 rounds need a URS of depth 32. *)
+  && raises (fun () ->
+         batch_accumulator_generate ~rounds:[| 5 |] urs 1 (Array.sub chals 0 5))
+
+let%test "batch_accumulator_failures" =
+  let rng = Marlin_plonk_bindings_pasta_rng.create "accumulators" in
+  let urs = create 16 in
+  let chals = Marlin_plonk_bindings_pasta_rng.fp_array rng 24 in
+  let comms = batch_accumulator_generate urs 6 chals in
+  let bad = Array.copy comms in
+  bad.(1) <- comms.(0) ;
+  bad.(4) <- comms.(5) ;
+  batch_accumulator_failures ~rng urs comms chals = [||]
+  && batch_accumulator_failures ~rng urs bad chals = [| 1; 4 |]
+  && not (batch_accumulator_check ~rng urs bad chals)
+
+let%test "b_poly_commitments" =
+  let rng = Marlin_plonk_bindings_pasta_rng.create "b_poly" in
+  let urs = create 16 in
+  let chals =
+    Array.init 3 (fun k -> Marlin_plonk_bindings_pasta_rng.fp_array rng (k + 2))
+  in
+  b_poly_commitments urs chals = Array.map (b_poly_commitment urs) chals
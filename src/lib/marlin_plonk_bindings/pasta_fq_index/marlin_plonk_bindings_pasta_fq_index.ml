@@ -14,6 +14,86 @@ module Gate_vector = struct
   external wrap : t -> Plonk_gate.Wire.t -> Plonk_gate.Wire.t -> unit
     = "caml_pasta_fq_plonk_gate_vector_wrap"
 
+  (** [connect t a b] declares that the wires [a] and [b] hold the same
+      variable. The permutation cycles are computed from these declarations
+      when the index is created, and take precedence over [wrap]. *)
+  external connect : t -> Plonk_gate.Wire.t -> Plonk_gate.Wire.t -> unit
+    = "caml_pasta_fq_plonk_gate_vector_connect"
+
+  (** Rewires the gates according to the wires connected so far. This is only
+      needed to inspect the wiring before the index is created. Raises
+      [Failure] if a connected wire is beyond the last gate.
+
+      The gates of [t] are modified in place, and [get] returns the rewired
+      gates afterwards. The queries below that work on the wiring of the
+      circuit ([check_witness], [write_witness_csv], [stats], [digest],
+      [diff], [write_json] and [write_dot]) call it first, so they modify [t]
+      in the same way. *)
+  external apply_wiring : t -> unit
+    = "caml_pasta_fq_plonk_gate_vector_apply_wiring"
+
+  (** [check_witness t ~public witness ~limit] checks the witness, laid out as
+      for [Marlin_plonk_bindings_pasta_fq_proof.create], against the gates and
+      their wiring. It reports the first [limit] failing rows, whose gate
+      constraint does not hold or whose wires differ from the wires they are
+      connected to. Raises [Invalid_argument] if there is no domain for the
+      circuit. Rewires [t] as [apply_wiring] does. *)
+  external check_witness :
+       t
+    -> public:int
+    -> Marlin_plonk_bindings_pasta_fq_vector.t
+    -> limit:int
+    -> Marlin_plonk_bindings_pasta_fq.t Plonk_witness_check.t
+    = "caml_pasta_fq_plonk_gate_vector_check_witness"
+
+  (** [write_witness_csv t witness ~first ~last path] writes the gates and
+      witness values of the rows [first] to [last] to [path] as CSV. Rewires
+      [t] as [apply_wiring] does. *)
+  external write_witness_csv :
+       t
+    -> Marlin_plonk_bindings_pasta_fq_vector.t
+    -> first:int
+    -> last:int
+    -> string
+    -> unit = "caml_pasta_fq_plonk_gate_vector_write_witness_csv"
+
+  (** [stats t ~public urs] reports the shape of the circuit as [create]
+      would build it with [public] public inputs, and compares the depth of
+      [urs], if given, with the depth the circuit needs. Rewires [t] as
+      [apply_wiring] does. *)
+  external stats :
+       t
+    -> public:int
+    -> Marlin_plonk_bindings_pasta_fq_urs.t option
+    -> Plonk_circuit_stats.t = "caml_pasta_fq_plonk_gate_vector_stats"
+
+  (** [digest t ~public] is a digest of the gates, their wiring, the number of
+      public inputs and the size of the domain. It is equal to the [digest] of
+      the index that [create] builds from [t] with [public] public inputs.
+      Rewires [t] as [apply_wiring] does. *)
+  external digest : t -> public:int -> Bytes.t
+    = "caml_pasta_fq_plonk_gate_vector_digest"
+
+  (** [diff before after] compares the gates of two circuits row by row,
+      after rewiring both as [apply_wiring] does. *)
+  external diff :
+       t
+    -> t
+    -> Marlin_plonk_bindings_pasta_fq.t Plonk_circuit_diff.t
+    = "caml_pasta_fq_plonk_gate_vector_diff"
+
+  (** [write_json ?rows t path] writes the gates in [rows], or all of them, to
+      [path] as a JSON array, with their coefficients as decimal strings.
+      Rewires [t] as [apply_wiring] does. *)
+  external write_json : ?rows:Plonk_row_selection.t -> t -> string -> unit
+    = "caml_pasta_fq_plonk_gate_vector_write_json"
+
+  (** [write_dot ?rows t path] writes the gates in [rows], or all of them, to
+      [path] as a Graphviz DOT graph, with an edge for each wire between two of
+      these rows. Rewires [t] as [apply_wiring] does. *)
+  external write_dot : ?rows:Plonk_row_selection.t -> t -> string -> unit
+    = "caml_pasta_fq_plonk_gate_vector_write_dot"
+
   let%test "gate vector" =
     let vec = create () in
     let l : Plonk_gate.Wire.t = { row = 1; col = L } in
@@ -47,9 +127,26 @@ external domain_d4_size : t -> int = "caml_pasta_fq_plonk_index_domain_d4_size"
 
 external domain_d8_size : t -> int = "caml_pasta_fq_plonk_index_domain_d8_size"
 
+(** The shape of the circuit of the index, as [Gate_vector.stats] reports it
+    for the gate vector the index was created from. *)
+external stats : t -> Plonk_circuit_stats.t = "caml_pasta_fq_plonk_index_stats"
+
+external digest : t -> Bytes.t = "caml_pasta_fq_plonk_index_digest"
+
+(** [diff before after] compares the circuits of two indices row by row, and
+    their numbers of public inputs. *)
+external diff : t -> t -> Marlin_plonk_bindings_pasta_fq.t Plonk_circuit_diff.t
+  = "caml_pasta_fq_plonk_index_diff"
+
+(** [read ?offset ?digest urs path] reads an index written by [write]. If
+    [digest] is given, raises [Failure] unless the index was built for the
+    circuit with that digest. *)
 external read :
-  ?offset:int -> Marlin_plonk_bindings_pasta_fq_urs.t -> string -> t
-  = "caml_pasta_fq_plonk_index_read"
+     ?offset:int
+  -> ?digest:Bytes.t
+  -> Marlin_plonk_bindings_pasta_fq_urs.t
+  -> string
+  -> t = "caml_pasta_fq_plonk_index_read"
 
 external write : ?append:bool -> t -> string -> unit
   = "caml_pasta_fq_plonk_index_write"
@@ -24,6 +24,12 @@ external dummy : unit -> t = "caml_pasta_fq_plonk_verifier_index_dummy"
 
 external deep_copy : t -> t = "caml_pasta_fq_plonk_verifier_index_deep_copy"
 
+(** [diff before after] is the names of the fields of [before] and [after]
+    that differ, such as ["ql_comm"] or ["shifts"]. The URSs are not
+    compared. *)
+external diff : t -> t -> string array
+  = "caml_pasta_fq_plonk_verifier_index_diff"
+
 let%test "deep_copy" =
   let x = dummy () in
   deep_copy x = x
@@ -8,13 +8,100 @@ end
 
 external create : int -> t = "caml_pasta_fq_urs_create"
 
+external depth : t -> int = "caml_pasta_fq_urs_depth"
+
+(** [extend urs depth] is a URS of the given depth which shares its first
+    [depth urs] points with [urs]. Only the missing points are derived.
+    Commitments to polynomials that fit in [urs] are unchanged. Raises
+    [Failure] if [urs] was not derived by [create]. *)
+external extend : t -> int -> t = "caml_pasta_fq_urs_extend"
+
+(** [trim urs depth] is the URS made of the first [depth] points of [urs]. *)
+external trim : t -> int -> t = "caml_pasta_fq_urs_trim"
+
+(** A digest of the points of the URS. Two URSs are the same exactly when
+    their digests are. *)
+external digest : t -> Bytes.t = "caml_pasta_fq_urs_digest"
+
+(** Checks that the points of the URS are the ones [create] derives. With
+    [sample_size], only that many randomly chosen points are checked. Raises
+    [Failure] if the derivation no longer matches the one [create] uses. *)
+external verify_derivation : ?sample_size:int -> t -> bool
+  = "caml_pasta_fq_urs_verify_derivation"
+
 external write : ?append:bool -> t -> string -> unit = "caml_pasta_fq_urs_write"
 
 external read : ?offset:int -> string -> t option = "caml_pasta_fq_urs_read"
 
+(** Atomically writes the URS to the given path, in a versioned container
+    format with a checksum. *)
+external save : t -> string -> unit = "caml_pasta_fq_urs_save"
+
+(** Reads a URS written by [save]. Raises [Failure] describing the problem if
+    the file is for the other curve, truncated, corrupted or followed by extra
+    bytes. *)
+external load : string -> t = "caml_pasta_fq_urs_load"
+
+(** Writes the URS in a fixed-stride layout that can be memory-mapped with
+    [Mapped.create]. *)
+external save_mapped : t -> string -> unit = "caml_pasta_fq_urs_save_mapped"
+
+(** A memory-mapped URS file. Points are decoded and checked when they are
+    used, and the file's pages are shared between processes. Every point is
+    also checked on a background thread, whose result is given by [checked]
+    and [wait_checked]. *)
+module Mapped = struct
+  type nonrec urs = t
+
+  type t
+
+  external create : string -> t = "caml_pasta_fq_urs_map"
+
+  external depth : t -> int = "caml_pasta_fq_mapped_urs_depth"
+
+  external g : t -> int -> Marlin_plonk_bindings_pasta_pallas.Affine.t
+    = "caml_pasta_fq_mapped_urs_g"
+
+  external h : t -> Marlin_plonk_bindings_pasta_pallas.Affine.t
+    = "caml_pasta_fq_mapped_urs_h"
+
+  (** Whether every point is valid, or [None] while the points are still
+      being checked. *)
+  external checked : t -> bool option = "caml_pasta_fq_mapped_urs_checked"
+
+  (** Waits until every point has been checked. Raises [Failure] describing
+      the first invalid point, if any. *)
+  external wait_checked : t -> unit = "caml_pasta_fq_mapped_urs_wait_checked"
+
+  (** [commit t coeffs] is the commitment to the polynomial with coefficients
+      [coeffs], as [commit] gives it without a degree bound. Only the first
+      [Array.length coeffs] points are decoded. *)
+  external commit :
+    t -> Marlin_plonk_bindings_pasta_fq.t array -> Poly_comm.t
+    = "caml_pasta_fq_mapped_urs_commit"
+
+  (** Decodes all of the points, in parallel, for example to create an index. *)
+  external to_urs : t -> urs = "caml_pasta_fq_mapped_urs_to_urs"
+end
+
 external lagrange_commitment : t -> domain_size:int -> int -> Poly_comm.t
   = "caml_pasta_fq_urs_lagrange_commitment"
 
+(** Computes the commitments to every Lagrange basis polynomial of the domain
+    at once, and caches them in the URS for [lagrange_commitment]. *)
+external lagrange_commitments : t -> domain_size:int -> Poly_comm.t array
+  = "caml_pasta_fq_urs_lagrange_commitments"
+
+(** Persists the cached Lagrange basis commitments, for example next to the
+    URS file. *)
+external write_lagrange_bases : t -> string -> unit
+  = "caml_pasta_fq_urs_write_lagrange_bases"
+
+(** Restores the commitments written by [write_lagrange_bases] into the cache.
+    Raises [Failure] if they were computed for a different URS. *)
+external read_lagrange_bases : t -> string -> unit
+  = "caml_pasta_fq_urs_read_lagrange_bases"
+
 external commit_evaluations :
   t -> domain_size:int -> Marlin_plonk_bindings_pasta_fq.t array -> Poly_comm.t
   = "caml_pasta_fq_urs_commit_evaluations"
@@ -23,18 +110,109 @@ external b_poly_commitment :
   t -> Marlin_plonk_bindings_pasta_fq.t array -> Poly_comm.t
   = "caml_pasta_fq_urs_b_poly_commitment"
 
+(** Commits to the [b] polynomial of each set of challenges, in parallel. *)
+external b_poly_commitments :
+  t -> Marlin_plonk_bindings_pasta_fq.t array array -> Poly_comm.t array
+  = "caml_pasta_fq_urs_b_poly_commitments"
+
+(** Checks the accumulators against their challenges, concatenated. [rounds]
+    gives the number of challenges of each accumulator, which by default all
+    have the same number. Raises [Failure] if the challenges don't match.
+    The random combination is drawn from [rng] if given. *)
 external batch_accumulator_check :
-     t
+     ?rng:Marlin_plonk_bindings_pasta_rng.t
+  -> ?rounds:int array
+  -> t
   -> Marlin_plonk_bindings_pasta_pallas.Affine.t array
   -> Marlin_plonk_bindings_pasta_fq.t array
   -> bool = "caml_pasta_fq_urs_batch_accumulator_check"
 
+(** As [batch_accumulator_check], but returns the indices of the accumulators
+    that fail, in increasing order, by bisecting the batch. *)
+external batch_accumulator_failures :
+     ?rng:Marlin_plonk_bindings_pasta_rng.t
+  -> ?rounds:int array
+  -> t
+  -> Marlin_plonk_bindings_pasta_pallas.Affine.t array
+  -> Marlin_plonk_bindings_pasta_fq.t array
+  -> int array = "caml_pasta_fq_urs_batch_accumulator_failures"
+
 external batch_accumulator_generate :
-     t
+     ?rounds:int array
+  -> t
   -> int
   -> Marlin_plonk_bindings_pasta_fq.t array
-  -> Marlin_plonk_bindings_pasta_pallas.Affine.t
+  -> Marlin_plonk_bindings_pasta_pallas.Affine.t array
   = "caml_pasta_fq_urs_batch_accumulator_generate"
 
 external h : t -> Marlin_plonk_bindings_pasta_pallas.Affine.t
   = "caml_pasta_fq_urs_h"
+
+module Opening_proof = struct
+  type t =
+    ( Marlin_plonk_bindings_pasta_fq.t
+    , Marlin_plonk_bindings_pasta_pallas.Affine.t )
+    Marlin_plonk_bindings_types.Plonk_proof.Opening_proof.t
+end
+
+(** The blinding factors of the parts of a hiding commitment. *)
+module Blinders = struct
+  type t =
+    Marlin_plonk_bindings_pasta_fq.t Marlin_plonk_bindings_types.Poly_comm.t
+end
+
+(** Commits to the polynomial with the given coefficients, shifted for the
+    degree bound [max_degree] if given. *)
+external commit :
+     t
+  -> Marlin_plonk_bindings_pasta_fq.t array
+  -> max_degree:int option
+  -> Poly_comm.t
+  = "caml_pasta_fq_poly_comm_commit"
+
+(** As [commit], but blinds each part of the commitment with [r h] for a fresh
+    random [r]. Returns the commitment with the blinding factors of its parts,
+    which [open_] takes to open it. *)
+external commit_hiding :
+     t
+  -> Marlin_plonk_bindings_pasta_fq.t array
+  -> max_degree:int option
+  -> Poly_comm.t * Blinders.t
+  = "caml_pasta_fq_poly_comm_commit_hiding"
+
+(** The evaluations of each chunk of the polynomial, as expected by
+    [verify_opening]. *)
+external evaluate :
+     t
+  -> Marlin_plonk_bindings_pasta_fq.t array
+  -> Marlin_plonk_bindings_pasta_fq.t
+  -> Marlin_plonk_bindings_pasta_fq.t array
+  = "caml_pasta_fq_poly_comm_evaluate"
+
+(** Creates an opening proof of the polynomials, with their degree bounds, at
+    all of the points. Each polynomial comes with the blinding factors returned
+    by [commit_hiding], or [None] if it was committed to with [commit]. The pair
+    holds the [polyscale] and [evalscale] challenges used to combine the
+    polynomials and the points. *)
+external open_ :
+     t
+  -> ( Marlin_plonk_bindings_pasta_fq.t array
+     * int option
+     * Blinders.t option )
+     array
+  -> Marlin_plonk_bindings_pasta_fq.t array
+  -> Marlin_plonk_bindings_pasta_fq.t * Marlin_plonk_bindings_pasta_fq.t
+  -> Opening_proof.t = "caml_pasta_fq_poly_comm_open"
+
+(** Verifies a proof made by [open_]. Each commitment comes with its degree
+    bound and its chunked evaluations at each of the points. *)
+external verify_opening :
+     t
+  -> ( Poly_comm.t
+     * int option
+     * Marlin_plonk_bindings_pasta_fq.t array array )
+     array
+  -> Marlin_plonk_bindings_pasta_fq.t array
+  -> Marlin_plonk_bindings_pasta_fq.t * Marlin_plonk_bindings_pasta_fq.t
+  -> Opening_proof.t
+  -> bool = "caml_pasta_fq_poly_comm_verify"
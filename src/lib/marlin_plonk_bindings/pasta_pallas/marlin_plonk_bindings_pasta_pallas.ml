@@ -19,6 +19,19 @@ external double : t -> t = "caml_pasta_pallas_double"
 external scale : t -> Marlin_plonk_bindings_pasta_fq.t -> t
   = "caml_pasta_pallas_scale"
 
+(** [msm points scalars] computes the multi-scalar multiplication
+    [sum_i scalars.(i) * points.(i)]. *)
+external msm : Affine.t array -> Marlin_plonk_bindings_pasta_fq.t array -> t
+  = "caml_pasta_pallas_msm"
+
+(** As [msm], but splits the input into [chunk_size] terms computed in
+    parallel. Intended for very large inputs. *)
+external msm_chunked :
+     Affine.t array
+  -> Marlin_plonk_bindings_pasta_fq.t array
+  -> chunk_size:int
+  -> t = "caml_pasta_pallas_msm_chunked"
+
 external random : unit -> t = "caml_pasta_pallas_random"
 
 external rng : int -> t = "caml_pasta_pallas_rng"
@@ -27,6 +40,14 @@ external to_affine : t -> Affine.t = "caml_pasta_pallas_to_affine"
 
 external of_affine : Affine.t -> t = "caml_pasta_pallas_of_affine"
 
+(** As [Array.map to_affine], but shares a single field inversion between all
+    of the points. *)
+external batch_to_affine : t array -> Affine.t array
+  = "caml_pasta_pallas_batch_to_affine"
+
+external batch_of_affine : Affine.t array -> t array
+  = "caml_pasta_pallas_batch_of_affine"
+
 external of_affine_coordinates :
   Marlin_plonk_bindings_pasta_fp.t -> Marlin_plonk_bindings_pasta_fp.t -> t
   = "caml_pasta_pallas_of_affine_coordinates"
@@ -37,9 +58,92 @@ external endo_base : unit -> Marlin_plonk_bindings_pasta_fp.t
 external endo_scalar : unit -> Marlin_plonk_bindings_pasta_fq.t
   = "caml_pasta_pallas_endo_scalar"
 
+(** [scalar_challenge_to_field endo length_in_bits chal] is the scalar
+    represented by the [length_in_bits]-bit challenge [chal], for the scalar
+    field endomorphism coefficient [endo]. *)
+external scalar_challenge_to_field :
+     Marlin_plonk_bindings_pasta_fq.t
+  -> int
+  -> Marlin_plonk_bindings_bigint_256.t
+  -> Marlin_plonk_bindings_pasta_fq.t
+  = "caml_pasta_pallas_scalar_challenge_to_field"
+
+(** [endo_scale p chal length_in_bits] is
+    [scale p (scalar_challenge_to_field (endo_scalar ()) length_in_bits chal)],
+    computed with the base field endomorphism as in the [Endomul] gates. *)
+external endo_scale : t -> Marlin_plonk_bindings_bigint_256.t -> int -> t
+  = "caml_pasta_pallas_endo_scale"
+
+external of_affine_coordinates_checked :
+  Marlin_plonk_bindings_pasta_fp.t -> Marlin_plonk_bindings_pasta_fp.t -> t
+  = "caml_pasta_pallas_of_affine_coordinates_checked"
+
+external is_on_curve : t -> bool = "caml_pasta_pallas_is_on_curve"
+
+external affine_is_on_curve : Affine.t -> bool
+  = "caml_pasta_pallas_affine_is_on_curve"
+
+(** 32-byte encoding of the x coordinate with the parity of y in the top bit.
+    Decoding raises on non-canonical or off-curve inputs. *)
+external affine_to_compressed_bytes : Affine.t -> Bytes.t
+  = "caml_pasta_pallas_affine_to_compressed_bytes"
+
+external affine_of_compressed_bytes : Bytes.t -> Affine.t
+  = "caml_pasta_pallas_affine_of_compressed_bytes"
+
+(** 64-byte encoding of both coordinates. Decoding raises on non-canonical or
+    off-curve inputs. *)
+external affine_to_uncompressed_bytes : Affine.t -> Bytes.t
+  = "caml_pasta_pallas_affine_to_uncompressed_bytes"
+
+external affine_of_uncompressed_bytes : Bytes.t -> Affine.t
+  = "caml_pasta_pallas_affine_of_uncompressed_bytes"
+
 external affine_deep_copy : Affine.t -> Affine.t
   = "caml_pasta_pallas_affine_deep_copy"
 
+(** A precomputed table of the multiples of a fixed base point. [scale] with a
+    table is several times faster than [scale] with the base itself. *)
+module Fixed_base = struct
+  type group = t
+
+  type t
+
+  (** [create base ~window] builds a table of [2^window] points for each
+      [window] bits of the scalar. [window] must be between 1 and 16. *)
+  external create : group -> window:int -> t
+    = "caml_pasta_pallas_fixed_base_create"
+
+  external base : t -> Affine.t = "caml_pasta_pallas_fixed_base_base"
+
+  external scale : t -> Marlin_plonk_bindings_pasta_fq.t -> group
+    = "caml_pasta_pallas_fixed_base_scale"
+
+  external batch_scale :
+    t -> Marlin_plonk_bindings_pasta_fq.t array -> group array
+    = "caml_pasta_pallas_fixed_base_batch_scale"
+end
+
+(** The group map used by the prover and verifier to send base field elements
+    onto the curve. *)
+module Group_map = struct
+  type t
+
+  external create : unit -> t = "caml_pasta_pallas_group_map_create"
+
+  external to_group : t -> Marlin_plonk_bindings_pasta_fp.t -> Affine.t
+    = "caml_pasta_pallas_group_map_to_group"
+
+  external batch_to_group :
+    t -> Marlin_plonk_bindings_pasta_fp.t array -> Affine.t array
+    = "caml_pasta_pallas_group_map_batch_to_group"
+
+  (** Absorbs the bytes into a Poseidon sponge and maps the squeezed field
+      element onto the curve. *)
+  external hash_to_curve : t -> Bytes.t -> Affine.t
+    = "caml_pasta_pallas_group_map_hash_to_curve"
+end
+
 (* tests *)
 
 let%test_module _ =
@@ -74,4 +178,73 @@ let%test_module _ =
         ( "2043704922874314040385013091576698457103021424623870194379792173147242419946"
         , "5363959817269906935062331974892998553523781697031104933152007068461890921147"
         )
+
+    let%test "compressed round trip" =
+      let x = random () |> to_affine in
+      affine_of_compressed_bytes (affine_to_compressed_bytes x) = x
+      && affine_of_uncompressed_bytes (affine_to_uncompressed_bytes x) = x
+
+    let%test "rejects off-curve points" =
+      let open Marlin_plonk_bindings_pasta_fp in
+      let bytes =
+        affine_to_uncompressed_bytes
+          (Marlin_plonk_bindings_types.Or_infinity.Finite (of_int 1, of_int 1))
+      in
+      (not (is_on_curve (of_affine_coordinates (of_int 1) (of_int 1))))
+      &&
+      match affine_of_uncompressed_bytes bytes with
+      | _ ->
+          false
+      | exception Failure _ ->
+          true
+
+    let%test "endo_scale" =
+      let p = random () in
+      let chal =
+        Marlin_plonk_bindings_bigint_256.of_hex_string
+          "0x9c3f1e8d2b7a60554e13c0ffee12ab34"
+      in
+      let x = scalar_challenge_to_field (endo_scalar ()) 128 chal in
+      to_affine (endo_scale p chal 128) = to_affine (scale p x)
+
+    let%test "batch_to_affine" =
+      let points = Array.init 10 (fun _ -> random ()) in
+      let points = Array.append points [| sub (one ()) (one ()) |] in
+      let affine = batch_to_affine points in
+      affine = Array.map to_affine points
+      && Array.map to_affine (batch_of_affine affine) = affine
+
+    let%test "fixed base" =
+      let table = Fixed_base.create (one ()) ~window:4 in
+      let xs = Array.init 5 (fun _ -> Marlin_plonk_bindings_pasta_fq.random ()) in
+      Fixed_base.base table = to_affine (one ())
+      && Array.map to_affine (Fixed_base.batch_scale table xs)
+         = Array.map (fun x -> to_affine (scale (one ()) x)) xs
+      && to_affine (Fixed_base.scale table xs.(0))
+         = to_affine (scale (one ()) xs.(0))
+
+    let%test "group map" =
+      let map = Group_map.create () in
+      let us = Array.init 5 (fun _ -> Marlin_plonk_bindings_pasta_fp.random ()) in
+      let points = Group_map.batch_to_group map us in
+      Array.for_all affine_is_on_curve points
+      && points.(0) = Group_map.to_group map us.(0)
+      && Group_map.hash_to_curve map (Bytes.of_string "mina")
+         = Group_map.hash_to_curve map (Bytes.of_string "mina")
+      && Group_map.hash_to_curve map (Bytes.of_string "mina")
+         <> Group_map.hash_to_curve map (Bytes.of_string "mina\000")
+
+    let%test "msm" =
+      let points = Array.init 10 (fun _ -> random ()) in
+      let scalars =
+        Array.init 10 (fun _ -> Marlin_plonk_bindings_pasta_fq.random ())
+      in
+      let expected =
+        Array.fold_left add (sub (one ()) (one ()))
+          (Array.map2 scale points scalars)
+      in
+      let affine = Array.map to_affine points in
+      to_affine (msm affine scalars) = to_affine expected
+      && to_affine (msm_chunked affine scalars ~chunk_size:3)
+         = to_affine expected
   end )
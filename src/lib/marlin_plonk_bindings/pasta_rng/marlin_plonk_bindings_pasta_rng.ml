@@ -0,0 +1,55 @@
+type t
+
+external create : string -> t = "caml_pasta_rng_create"
+
+(** [bytes t len] is the next [len] bytes of the stream. *)
+external bytes : t -> int -> Bytes.t = "caml_pasta_rng_bytes"
+
+external fp : t -> Marlin_plonk_bindings_pasta_fp.t = "caml_pasta_rng_fp"
+
+external fq : t -> Marlin_plonk_bindings_pasta_fq.t = "caml_pasta_rng_fq"
+
+external pallas : t -> Marlin_plonk_bindings_pasta_pallas.t
+  = "caml_pasta_rng_pallas"
+
+external vesta : t -> Marlin_plonk_bindings_pasta_vesta.t
+  = "caml_pasta_rng_vesta"
+
+external fp_array : t -> int -> Marlin_plonk_bindings_pasta_fp.t array
+  = "caml_pasta_rng_fp_array"
+
+external fq_array : t -> int -> Marlin_plonk_bindings_pasta_fq.t array
+  = "caml_pasta_rng_fq_array"
+
+external pallas_array : t -> int -> Marlin_plonk_bindings_pasta_pallas.t array
+  = "caml_pasta_rng_pallas_array"
+
+external vesta_array : t -> int -> Marlin_plonk_bindings_pasta_vesta.t array
+  = "caml_pasta_rng_vesta_array"
+
+let%test "same seed, same stream" =
+  let a = create "seed" in
+  let b = create "seed" in
+  let xs = fp_array a 16 in
+  let ys = fp_array b 16 in
+  Array.for_all2 Marlin_plonk_bindings_pasta_fp.equal xs ys
+  && Marlin_plonk_bindings_pasta_fq.equal (fq a) (fq b)
+
+let%test "stream advances" =
+  let rng = create "seed" in
+  let x = fp rng in
+  let y = fp rng in
+  not (Marlin_plonk_bindings_pasta_fp.equal x y)
+
+(* The ChaCha20 keystream, with a zero nonce, for the key Blake2s("seed"). *)
+let%test "known answer" =
+  let hex b =
+    let buf = Buffer.create (2 * Bytes.length b) in
+    Bytes.iter (fun c -> Printf.bprintf buf "%02x" (Char.code c)) b ;
+    Buffer.contents buf
+  in
+  let rng = create "seed" in
+  let first = hex (bytes rng 16) in
+  let second = hex (bytes rng 16) in
+  first = "6eca04ae8aa45213c7e3af47bb014118"
+  && second = "553c4b5f2ed61dce0eb1fe7cc9f8bc1b"
@@ -19,6 +19,19 @@ external double : t -> t = "caml_pasta_vesta_double"
 external scale : t -> Marlin_plonk_bindings_pasta_fp.t -> t
   = "caml_pasta_vesta_scale"
 
+(** [msm points scalars] computes the multi-scalar multiplication
+    [sum_i scalars.(i) * points.(i)]. *)
+external msm : Affine.t array -> Marlin_plonk_bindings_pasta_fp.t array -> t
+  = "caml_pasta_vesta_msm"
+
+(** As [msm], but splits the input into [chunk_size] terms computed in
+    parallel. Intended for very large inputs. *)
+external msm_chunked :
+     Affine.t array
+  -> Marlin_plonk_bindings_pasta_fp.t array
+  -> chunk_size:int
+  -> t = "caml_pasta_vesta_msm_chunked"
+
 external random : unit -> t = "caml_pasta_vesta_random"
 
 external rng : int -> t = "caml_pasta_vesta_rng"
@@ -27,6 +40,14 @@ external to_affine : t -> Affine.t = "caml_pasta_vesta_to_affine"
 
 external of_affine : Affine.t -> t = "caml_pasta_vesta_of_affine"
 
+(** As [Array.map to_affine], but shares a single field inversion between all
+    of the points. *)
+external batch_to_affine : t array -> Affine.t array
+  = "caml_pasta_vesta_batch_to_affine"
+
+external batch_of_affine : Affine.t array -> t array
+  = "caml_pasta_vesta_batch_of_affine"
+
 external of_affine_coordinates :
   Marlin_plonk_bindings_pasta_fq.t -> Marlin_plonk_bindings_pasta_fq.t -> t
   = "caml_pasta_vesta_of_affine_coordinates"
@@ -37,9 +58,92 @@ external endo_base : unit -> Marlin_plonk_bindings_pasta_fq.t
 external endo_scalar : unit -> Marlin_plonk_bindings_pasta_fp.t
   = "caml_pasta_vesta_endo_scalar"
 
+(** [scalar_challenge_to_field endo length_in_bits chal] is the scalar
+    represented by the [length_in_bits]-bit challenge [chal], for the scalar
+    field endomorphism coefficient [endo]. *)
+external scalar_challenge_to_field :
+     Marlin_plonk_bindings_pasta_fp.t
+  -> int
+  -> Marlin_plonk_bindings_bigint_256.t
+  -> Marlin_plonk_bindings_pasta_fp.t
+  = "caml_pasta_vesta_scalar_challenge_to_field"
+
+(** [endo_scale p chal length_in_bits] is
+    [scale p (scalar_challenge_to_field (endo_scalar ()) length_in_bits chal)],
+    computed with the base field endomorphism as in the [Endomul] gates. *)
+external endo_scale : t -> Marlin_plonk_bindings_bigint_256.t -> int -> t
+  = "caml_pasta_vesta_endo_scale"
+
+external of_affine_coordinates_checked :
+  Marlin_plonk_bindings_pasta_fq.t -> Marlin_plonk_bindings_pasta_fq.t -> t
+  = "caml_pasta_vesta_of_affine_coordinates_checked"
+
+external is_on_curve : t -> bool = "caml_pasta_vesta_is_on_curve"
+
+external affine_is_on_curve : Affine.t -> bool
+  = "caml_pasta_vesta_affine_is_on_curve"
+
+(** 32-byte encoding of the x coordinate with the parity of y in the top bit.
+    Decoding raises on non-canonical or off-curve inputs. *)
+external affine_to_compressed_bytes : Affine.t -> Bytes.t
+  = "caml_pasta_vesta_affine_to_compressed_bytes"
+
+external affine_of_compressed_bytes : Bytes.t -> Affine.t
+  = "caml_pasta_vesta_affine_of_compressed_bytes"
+
+(** 64-byte encoding of both coordinates. Decoding raises on non-canonical or
+    off-curve inputs. *)
+external affine_to_uncompressed_bytes : Affine.t -> Bytes.t
+  = "caml_pasta_vesta_affine_to_uncompressed_bytes"
+
+external affine_of_uncompressed_bytes : Bytes.t -> Affine.t
+  = "caml_pasta_vesta_affine_of_uncompressed_bytes"
+
 external affine_deep_copy : Affine.t -> Affine.t
   = "caml_pasta_vesta_affine_deep_copy"
 
+(** A precomputed table of the multiples of a fixed base point. [scale] with a
+    table is several times faster than [scale] with the base itself. *)
+module Fixed_base = struct
+  type group = t
+
+  type t
+
+  (** [create base ~window] builds a table of [2^window] points for each
+      [window] bits of the scalar. [window] must be between 1 and 16. *)
+  external create : group -> window:int -> t
+    = "caml_pasta_vesta_fixed_base_create"
+
+  external base : t -> Affine.t = "caml_pasta_vesta_fixed_base_base"
+
+  external scale : t -> Marlin_plonk_bindings_pasta_fp.t -> group
+    = "caml_pasta_vesta_fixed_base_scale"
+
+  external batch_scale :
+    t -> Marlin_plonk_bindings_pasta_fp.t array -> group array
+    = "caml_pasta_vesta_fixed_base_batch_scale"
+end
+
+(** The group map used by the prover and verifier to send base field elements
+    onto the curve. *)
+module Group_map = struct
+  type t
+
+  external create : unit -> t = "caml_pasta_vesta_group_map_create"
+
+  external to_group : t -> Marlin_plonk_bindings_pasta_fq.t -> Affine.t
+    = "caml_pasta_vesta_group_map_to_group"
+
+  external batch_to_group :
+    t -> Marlin_plonk_bindings_pasta_fq.t array -> Affine.t array
+    = "caml_pasta_vesta_group_map_batch_to_group"
+
+  (** Absorbs the bytes into a Poseidon sponge and maps the squeezed field
+      element onto the curve. *)
+  external hash_to_curve : t -> Bytes.t -> Affine.t
+    = "caml_pasta_vesta_group_map_hash_to_curve"
+end
+
 (* tests*)
 
 let%test_module _ =
@@ -66,12 +170,124 @@ let%test_module _ =
         , "11426906929455361843568202299992114520848200991084027513389447476559454104162"
         )
 
+    (* The coordinates of [scale (one ()) 5]. *)
+    let five =
+      ( "13486096822601787212198473175598632436276692382164101871628408648731182981277"
+      , "605204994206592296804149234467312053390246487302138089171444931383680327812"
+      )
+
     let%test "scale" =
       let x = one () in
       let k = Marlin_plonk_bindings_pasta_fp.of_int 5 in
       let point = scale x k in
-      is_same_point point
-        ( "13486096822601787212198473175598632436276692382164101871628408648731182981277"
-        , "605204994206592296804149234467312053390246487302138089171444931383680327812"
-        )
+      is_same_point point five
+
+    let bytes_of_hex hex =
+      Bytes.init (String.length hex / 2) (fun i ->
+          Char.chr (int_of_string ("0x" ^ String.sub hex (2 * i) 2)))
+
+    let%test "compressed encoding" =
+      let five_times =
+        scale (one ()) (Marlin_plonk_bindings_pasta_fp.of_int 5)
+      in
+      affine_to_compressed_bytes (to_affine (one ()))
+      = bytes_of_hex
+          "0100000000000000000000000000000000000000000000000000000000000000"
+      && affine_to_compressed_bytes (to_affine five_times)
+         = bytes_of_hex
+             "9d60c3d462c7ead0275761e48e491de89319580d32b89f6d1d28e4d43cdcd01d"
+
+    let%test "compressed round trip" =
+      let x = random () |> to_affine in
+      affine_of_compressed_bytes (affine_to_compressed_bytes x) = x
+      && affine_of_uncompressed_bytes (affine_to_uncompressed_bytes x) = x
+      && affine_of_compressed_bytes
+           (affine_to_compressed_bytes (to_affine (sub (one ()) (one ()))))
+         = Marlin_plonk_bindings_types.Or_infinity.Infinity
+
+    let%test "rejects off-curve points" =
+      let open Marlin_plonk_bindings_pasta_fq in
+      let bytes =
+        affine_to_uncompressed_bytes
+          (Marlin_plonk_bindings_types.Or_infinity.Finite (of_int 1, of_int 1))
+      in
+      (not (is_on_curve (of_affine_coordinates (of_int 1) (of_int 1))))
+      &&
+      match affine_of_uncompressed_bytes bytes with
+      | _ ->
+          false
+      | exception Failure _ ->
+          true
+
+    let%test "endo" =
+      (* The endomorphism [(x, y) -> (endo_base * x, y)] is the multiplication
+         by [endo_scalar]. *)
+      let open Marlin_plonk_bindings_types.Or_infinity in
+      match to_affine (one ()) with
+      | Infinity ->
+          false
+      | Finite (x, y) ->
+          to_affine (scale (one ()) (endo_scalar ()))
+          = Finite (Marlin_plonk_bindings_pasta_fq.mul (endo_base ()) x, y)
+
+    let%test "endo_scale" =
+      let p = random () in
+      let chal =
+        Marlin_plonk_bindings_bigint_256.of_hex_string
+          "0x9c3f1e8d2b7a60554e13c0ffee12ab34"
+      in
+      let x = scalar_challenge_to_field (endo_scalar ()) 128 chal in
+      to_affine (endo_scale p chal 128) = to_affine (scale p x)
+
+    let%test "batch_to_affine" =
+      let points = Array.init 10 (fun _ -> random ()) in
+      let points = Array.append points [| sub (one ()) (one ()) |] in
+      let affine = batch_to_affine points in
+      affine = Array.map to_affine points
+      && Array.map to_affine (batch_of_affine affine) = affine
+
+    let%test "fixed base" =
+      let table = Fixed_base.create (one ()) ~window:4 in
+      let xs =
+        Array.init 5 (fun _ -> Marlin_plonk_bindings_pasta_fp.random ())
+      in
+      Fixed_base.base table = to_affine (one ())
+      && is_same_point
+           (Fixed_base.scale table (Marlin_plonk_bindings_pasta_fp.of_int 5))
+           five
+      && Array.map to_affine (Fixed_base.batch_scale table xs)
+         = Array.map (fun x -> to_affine (scale (one ()) x)) xs
+
+    let%test "group map" =
+      let map = Group_map.create () in
+      let us =
+        Array.init 5 (fun _ -> Marlin_plonk_bindings_pasta_fq.random ())
+      in
+      let points = Group_map.batch_to_group map us in
+      Array.for_all affine_is_on_curve points
+      && points.(0) = Group_map.to_group map us.(0)
+      && Group_map.hash_to_curve map (Bytes.of_string "mina")
+         = Group_map.hash_to_curve map (Bytes.of_string "mina")
+      && Group_map.hash_to_curve map (Bytes.of_string "mina")
+         <> Group_map.hash_to_curve map (Bytes.of_string "mina\000")
+
+    let%test "msm" =
+      let points = Array.init 10 (fun _ -> random ()) in
+      let scalars =
+        Array.init 10 (fun _ -> Marlin_plonk_bindings_pasta_fp.random ())
+      in
+      let expected =
+        Array.fold_left add (sub (one ()) (one ()))
+          (Array.map2 scale points scalars)
+      in
+      let affine = Array.map to_affine points in
+      let two_and_three =
+        msm
+          [| to_affine (one ()); to_affine (one ()) |]
+          Marlin_plonk_bindings_pasta_fp.[| of_int 2; of_int 3 |]
+      in
+      to_affine (msm affine scalars) = to_affine expected
+      && to_affine (msm_chunked affine scalars ~chunk_size:3)
+         = to_affine expected
+      && is_same_point two_and_three five
   end )
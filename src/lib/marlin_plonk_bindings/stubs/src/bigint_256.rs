@@ -11,6 +11,8 @@ const BIGINT256_LIMB_BYTES: i32 = BIGINT256_LIMB_BITS / 8;
 const BIGINT256_NUM_LIMBS: i32 =
     (BIGINT256_NUM_BITS + BIGINT256_LIMB_BITS - 1) / BIGINT256_LIMB_BITS;
 const BIGINT256_NUM_BYTES: usize = (BIGINT256_NUM_LIMBS as usize) * 8;
+// OCaml native ints are 63 bits wide, so this is [max_int].
+const OCAML_MAX_INT: u64 = (1 << 62) - 1;
 
 pub fn to_biguint(x: &BigInteger256) -> BigUint {
     let x_ = x.0.as_ptr() as *const u8;
@@ -27,6 +29,21 @@ pub fn of_biguint(x: &BigUint) -> BigInteger256 {
     BigInteger256(*limbs)
 }
 
+/// Reduces `x` modulo 2^256.
+fn of_biguint_wrapping(x: &BigUint) -> BigInteger256 {
+    let mut bytes = x.to_bytes_le();
+    bytes.truncate(BIGINT256_NUM_BYTES);
+    of_biguint(&BigUint::from_bytes_le(&bytes))
+}
+
+fn of_biguint_checked(x: &BigUint) -> Option<BigInteger256> {
+    if x.bits() > BIGINT256_NUM_BITS as usize {
+        None
+    } else {
+        Some(of_biguint(x))
+    }
+}
+
 #[ocaml::func]
 pub fn caml_bigint_256_of_numeral(
     s: &[u8],
@@ -72,6 +89,124 @@ pub fn caml_bigint_256_div(
     of_biguint(&res)
 }
 
+#[ocaml::func]
+pub fn caml_bigint_256_mod(
+    x: ocaml::Pointer<BigInteger256>,
+    y: ocaml::Pointer<BigInteger256>,
+) -> Result<BigInteger256, ocaml::Error> {
+    if y.as_ref().is_zero() {
+        return Err(ocaml::Error::invalid_argument("caml_bigint_256_mod")
+            .err()
+            .unwrap());
+    }
+    let res: BigUint = to_biguint(x.as_ref()) % to_biguint(y.as_ref());
+    Ok(of_biguint(&res))
+}
+
+#[ocaml::func]
+pub fn caml_bigint_256_add(
+    x: ocaml::Pointer<BigInteger256>,
+    y: ocaml::Pointer<BigInteger256>,
+) -> (BigInteger256, bool) {
+    let mut res = *x.as_ref();
+    let carry = res.add_nocarry(y.as_ref());
+    (res, carry)
+}
+
+#[ocaml::func]
+pub fn caml_bigint_256_sub(
+    x: ocaml::Pointer<BigInteger256>,
+    y: ocaml::Pointer<BigInteger256>,
+) -> (BigInteger256, bool) {
+    let mut res = *x.as_ref();
+    let borrow = res.sub_noborrow(y.as_ref());
+    (res, borrow)
+}
+
+/// Returns the full 512-bit product as its (low, high) halves.
+#[ocaml::func]
+pub fn caml_bigint_256_mul(
+    x: ocaml::Pointer<BigInteger256>,
+    y: ocaml::Pointer<BigInteger256>,
+) -> (BigInteger256, BigInteger256) {
+    let res: BigUint = to_biguint(x.as_ref()) * to_biguint(y.as_ref());
+    let hi = &res >> BIGINT256_NUM_BITS as usize;
+    (of_biguint_wrapping(&res), of_biguint(&hi))
+}
+
+#[ocaml::func]
+pub fn caml_bigint_256_shift_left(
+    x: ocaml::Pointer<BigInteger256>,
+    n: ocaml::Int,
+) -> Result<BigInteger256, ocaml::Error> {
+    if n < 0 {
+        return Err(ocaml::Error::invalid_argument("caml_bigint_256_shift_left")
+            .err()
+            .unwrap());
+    }
+    if n >= BIGINT256_NUM_BITS as isize {
+        return Ok(BigInteger256::default());
+    }
+    let res: BigUint = to_biguint(x.as_ref()) << n as usize;
+    Ok(of_biguint_wrapping(&res))
+}
+
+#[ocaml::func]
+pub fn caml_bigint_256_shift_right(
+    x: ocaml::Pointer<BigInteger256>,
+    n: ocaml::Int,
+) -> Result<BigInteger256, ocaml::Error> {
+    if n < 0 {
+        return Err(
+            ocaml::Error::invalid_argument("caml_bigint_256_shift_right")
+                .err()
+                .unwrap(),
+        );
+    }
+    if n >= BIGINT256_NUM_BITS as isize {
+        return Ok(BigInteger256::default());
+    }
+    let res: BigUint = to_biguint(x.as_ref()) >> n as usize;
+    Ok(of_biguint(&res))
+}
+
+fn limbwise(x: &BigInteger256, y: &BigInteger256, f: impl Fn(u64, u64) -> u64) -> BigInteger256 {
+    let mut res = BigInteger256::default();
+    for i in 0..(BIGINT256_NUM_LIMBS as usize) {
+        res.0[i] = f(x.0[i], y.0[i]);
+    }
+    res
+}
+
+#[ocaml::func]
+pub fn caml_bigint_256_logand(
+    x: ocaml::Pointer<BigInteger256>,
+    y: ocaml::Pointer<BigInteger256>,
+) -> BigInteger256 {
+    limbwise(x.as_ref(), y.as_ref(), |a, b| a & b)
+}
+
+#[ocaml::func]
+pub fn caml_bigint_256_logor(
+    x: ocaml::Pointer<BigInteger256>,
+    y: ocaml::Pointer<BigInteger256>,
+) -> BigInteger256 {
+    limbwise(x.as_ref(), y.as_ref(), |a, b| a | b)
+}
+
+#[ocaml::func]
+pub fn caml_bigint_256_logxor(
+    x: ocaml::Pointer<BigInteger256>,
+    y: ocaml::Pointer<BigInteger256>,
+) -> BigInteger256 {
+    limbwise(x.as_ref(), y.as_ref(), |a, b| a ^ b)
+}
+
+#[ocaml::func]
+pub fn caml_bigint_256_num_bits(x: ocaml::Pointer<BigInteger256>) -> ocaml::Int {
+    x.as_ref().num_bits() as isize
+}
+
 #[ocaml::func]
 pub fn caml_bigint_256_compare(
     x: ocaml::Pointer<BigInteger256>,
@@ -94,6 +229,54 @@ pub fn caml_bigint_256_to_string(x: ocaml::Pointer<BigInteger256>) -> String {
     to_biguint(x.as_ref()).to_string()
 }
 
+#[ocaml::func]
+pub fn caml_bigint_256_of_hex_string(s: &[u8]) -> Result<BigInteger256, ocaml::Error> {
+    let s = if s.starts_with(b"0x") || s.starts_with(b"0X") {
+        &s[2..]
+    } else {
+        s
+    };
+    match BigUint::parse_bytes(s, 16)
+        .as_ref()
+        .and_then(of_biguint_checked)
+    {
+        Some(data) => Ok(data),
+        None => Err(
+            ocaml::Error::invalid_argument("caml_bigint_256_of_hex_string")
+                .err()
+                .unwrap(),
+        ),
+    }
+}
+
+#[ocaml::func]
+pub fn caml_bigint_256_to_hex_string(x: ocaml::Pointer<BigInteger256>) -> String {
+    to_biguint(x.as_ref()).to_str_radix(16)
+}
+
+#[ocaml::func]
+pub fn caml_bigint_256_of_int(i: ocaml::Int) -> Result<BigInteger256, ocaml::Error> {
+    if i < 0 {
+        return Err(ocaml::Error::invalid_argument("caml_bigint_256_of_int")
+            .err()
+            .unwrap());
+    }
+    Ok(BigInteger256::from(i as u64))
+}
+
+#[ocaml::func]
+pub fn caml_bigint_256_to_int(
+    x: ocaml::Pointer<BigInteger256>,
+) -> Result<ocaml::Int, ocaml::Error> {
+    let x = x.as_ref();
+    if x.0[1..].iter().any(|limb| *limb != 0) || x.0[0] > OCAML_MAX_INT {
+        return Err(ocaml::Error::failwith("caml_bigint_256_to_int: overflow")
+            .err()
+            .unwrap());
+    }
+    Ok(x.0[0] as isize)
+}
+
 #[ocaml::func]
 pub fn caml_bigint_256_test_bit(
     x: ocaml::Pointer<BigInteger256>,
@@ -0,0 +1,8 @@
+/// Copies `bytes` into a freshly allocated OCaml `bytes` value.
+pub fn of_slice(bytes: &[u8]) -> ocaml::Value {
+    let str = unsafe { ocaml::sys::caml_alloc_string(bytes.len()) };
+    unsafe {
+        core::ptr::copy_nonoverlapping(bytes.as_ptr(), ocaml::sys::string_val(str), bytes.len());
+        ocaml::Value::new(str)
+    }
+}
@@ -0,0 +1,63 @@
+use commitment_dlog::{
+    commitment::{CommitmentCurve, PolyComm},
+    srs::SRS,
+};
+use std::{cell::RefCell, collections::HashMap, ops::Deref, rc::Rc};
+
+use crate::urs_lagrange_basis;
+
+/* The URS as seen from OCaml. Alongside the SRS itself, this caches the commitments to the
+ * Lagrange basis polynomials for each domain size they have been requested for.
+ *
+ * This dereferences to the `Rc<SRS<G>>`, so that it can be used wherever the bare SRS was. */
+
+pub struct CamlUrs<G: CommitmentCurve> {
+    pub srs: Rc<SRS<G>>,
+    lagrange_bases: RefCell<HashMap<usize, Rc<Vec<PolyComm<G>>>>>,
+}
+
+impl<G: CommitmentCurve> CamlUrs<G> {
+    pub fn new(srs: Rc<SRS<G>>) -> Self {
+        CamlUrs {
+            srs,
+            lagrange_bases: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn cached_lagrange_basis(&self, domain_size: usize) -> Option<Rc<Vec<PolyComm<G>>>> {
+        self.lagrange_bases.borrow().get(&domain_size).cloned()
+    }
+
+    pub fn set_lagrange_basis(&self, domain_size: usize, basis: Vec<PolyComm<G>>) {
+        self.lagrange_bases
+            .borrow_mut()
+            .insert(domain_size, Rc::new(basis));
+    }
+
+    /// The domain sizes for which the Lagrange basis has been computed, in increasing order.
+    pub fn lagrange_basis_domain_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self.lagrange_bases.borrow().keys().cloned().collect();
+        sizes.sort();
+        sizes
+    }
+
+    /// Returns the commitments to every Lagrange basis polynomial of the domain of size
+    /// `domain_size`, computing and caching them on first use. Returns `None` if there is no
+    /// such domain.
+    pub fn lagrange_basis(&self, domain_size: usize) -> Option<Rc<Vec<PolyComm<G>>>> {
+        if let Some(basis) = self.cached_lagrange_basis(domain_size) {
+            return Some(basis);
+        }
+        let basis = urs_lagrange_basis::compute(&self.srs, domain_size)?;
+        self.set_lagrange_basis(domain_size, basis);
+        self.cached_lagrange_basis(domain_size)
+    }
+}
+
+impl<G: CommitmentCurve> Deref for CamlUrs<G> {
+    type Target = Rc<SRS<G>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.srs
+    }
+}
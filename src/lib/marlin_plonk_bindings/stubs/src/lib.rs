@@ -2,6 +2,10 @@ extern crate libc;
 
 /* Caml pointers */
 pub mod caml_pointer;
+/* Caml URS */
+pub mod caml_urs;
+/* Caml bytes */
+pub mod caml_bytes;
 /* Bigints */
 pub mod bigint_256;
 /* Fields */
@@ -13,12 +17,32 @@ pub mod pasta_fq_vector;
 /* Groups */
 pub mod pasta_vesta;
 pub mod pasta_pallas;
+/* Group maps */
+pub mod pasta_pallas_group_map;
+pub mod pasta_vesta_group_map;
+/* Fixed-base tables */
+pub mod pasta_pallas_fixed_base;
+pub mod pasta_vesta_fixed_base;
+/* Deterministic RNGs */
+pub mod pasta_rng;
 /* URS */
 pub mod pasta_fp_urs;
 pub mod pasta_fq_urs;
 pub mod urs_utils;
+pub mod urs_container;
+pub mod urs_mmap;
+pub mod urs_lagrange_basis;
+/* Polynomial commitments */
+pub mod pasta_fp_poly_comm;
+pub mod pasta_fq_poly_comm;
 /* Gates */
 pub mod plonk_gate;
+pub mod plonk_wiring;
+pub mod plonk_witness_check;
+pub mod plonk_circuit_stats;
+pub mod plonk_circuit_digest;
+pub mod plonk_circuit_diff;
+pub mod plonk_gate_export;
 /* Indices */
 pub mod index_serialization;
 pub mod plonk_verifier_index;
@@ -1,13 +1,15 @@
 use crate::bigint_256;
-use algebra::biginteger::BigInteger256;
+use algebra::biginteger::{BigInteger, BigInteger256};
 use mina_curves::pasta::fp::{Fp, FpParameters as Fp_params};
+use mina_curves::pasta::fq::{Fq, FqParameters as Fq_params};
 use algebra::{
-    fields::{Field, FpParameters, PrimeField, SquareRootField},
+    fields::{FftParameters, Field, FpParameters, LegendreSymbol, PrimeField, SquareRootField},
     FftField, One, UniformRand, Zero,
 };
 use ff_fft::{EvaluationDomain, Radix2EvaluationDomain as Domain};
 use num_bigint::BigUint;
 use rand::rngs::StdRng;
+use rayon::prelude::*;
 use std::cmp::Ordering::{Equal, Greater, Less};
 
 #[ocaml::func]
@@ -66,6 +68,25 @@ pub fn caml_pasta_fp_sqrt(x: ocaml::Pointer<Fp>) -> Option<Fp> {
     x.as_ref().sqrt()
 }
 
+#[ocaml::func]
+pub fn caml_pasta_fp_legendre(x: ocaml::Pointer<Fp>) -> ocaml::Int {
+    match x.as_ref().legendre() {
+        LegendreSymbol::Zero => 0,
+        LegendreSymbol::QuadraticResidue => 1,
+        LegendreSymbol::QuadraticNonResidue => -1,
+    }
+}
+
+#[ocaml::func]
+pub fn caml_pasta_fp_batch_sqrt(xs: Vec<Fp>) -> Vec<Option<Fp>> {
+    xs.par_iter().map(|x| x.sqrt()).collect()
+}
+
+#[ocaml::func]
+pub fn caml_pasta_fp_pow(x: ocaml::Pointer<Fp>, e: ocaml::Pointer<BigInteger256>) -> Fp {
+    x.as_ref().pow(e.as_ref())
+}
+
 #[ocaml::func]
 pub fn caml_pasta_fp_of_int(i: ocaml::Int) -> Fp {
     Fp::from(i as u64)
@@ -153,6 +174,145 @@ pub fn caml_pasta_fp_of_bigint(x: BigInteger256) -> Fp {
     Fp::from_repr(x)
 }
 
+/* Conversions to the other Pasta field, for non-native arithmetic. */
+
+#[ocaml::func]
+pub fn caml_pasta_fp_to_fq(x: ocaml::Pointer<Fp>) -> Result<Fq, ocaml::Error> {
+    let repr = x.as_ref().into_repr();
+    if repr >= Fq_params::MODULUS {
+        return Err(
+            ocaml::Error::failwith("caml_pasta_fp_to_fq: value does not fit")
+                .err()
+                .unwrap(),
+        );
+    }
+    Ok(Fq::from_repr(repr))
+}
+
+/// Splits `x` as `x = 2 * hi + lo`, with `lo` the low bit, as done by `shift_scalar`. `hi` always
+/// fits in the other field.
+#[ocaml::func]
+pub fn caml_pasta_fp_to_fq_split(x: ocaml::Pointer<Fp>) -> (Fq, bool) {
+    let mut repr = x.as_ref().into_repr();
+    let lo = repr.is_odd();
+    repr.div2();
+    (Fq::from_repr(repr), lo)
+}
+
+/// Decomposes `x` into `num_limbs` limbs of `limb_bits` bits each, least significant first.
+#[ocaml::func]
+pub fn caml_pasta_fp_to_fq_limbs(
+    x: ocaml::Pointer<Fp>,
+    limb_bits: ocaml::Int,
+    num_limbs: ocaml::Int,
+) -> Result<Vec<Fq>, ocaml::Error> {
+    let x = bigint_256::to_biguint(&x.as_ref().into_repr());
+    if limb_bits <= 0
+        || limb_bits >= Fq_params::MODULUS_BITS as isize
+        || num_limbs < 0
+        || limb_bits
+            .checked_mul(num_limbs)
+            .map_or(true, |bits| (x.bits() as isize) > bits)
+    {
+        return Err(ocaml::Error::invalid_argument("caml_pasta_fp_to_fq_limbs")
+            .err()
+            .unwrap());
+    }
+    let limb_bits = limb_bits as usize;
+    let mask = (BigUint::from(1u64) << limb_bits) - 1u64;
+    Ok((0..num_limbs as usize)
+        .map(|i| {
+            let limb = (&x >> (i * limb_bits)) & &mask;
+            Fq::from_repr(bigint_256::of_biguint(&limb))
+        })
+        .collect())
+}
+
+/// Recomposes limbs produced by [caml_pasta_fq_to_fp_limbs]. Fails if a limb is wider than
+/// `limb_bits` or the result does not fit in the field.
+#[ocaml::func]
+pub fn caml_pasta_fp_of_fq_limbs(
+    limbs: Vec<Fq>,
+    limb_bits: ocaml::Int,
+) -> Result<Fp, ocaml::Error> {
+    if limb_bits <= 0 || limb_bits >= Fq_params::MODULUS_BITS as isize {
+        return Err(ocaml::Error::invalid_argument("caml_pasta_fp_of_fq_limbs")
+            .err()
+            .unwrap());
+    }
+    let limb_bits = limb_bits as usize;
+    let mut x = BigUint::from(0u64);
+    for limb in limbs.iter().rev() {
+        let limb = bigint_256::to_biguint(&limb.into_repr());
+        if limb.bits() > limb_bits {
+            return Err(ocaml::Error::invalid_argument("caml_pasta_fp_of_fq_limbs")
+                .err()
+                .unwrap());
+        }
+        x = (x << limb_bits) | limb;
+    }
+    if x >= bigint_256::to_biguint(&Fp_params::MODULUS) {
+        return Err(
+            ocaml::Error::failwith("caml_pasta_fp_of_fq_limbs: value does not fit")
+                .err()
+                .unwrap(),
+        );
+    }
+    Ok(Fp::from_repr(bigint_256::of_biguint(&x)))
+}
+
+/// Returns the `len` least significant bits of `x`, least significant first. Fails if `x` does
+/// not fit in `len` bits.
+#[ocaml::func]
+pub fn caml_pasta_fp_to_bits(
+    x: ocaml::Pointer<Fp>,
+    len: ocaml::Int,
+) -> Result<Vec<bool>, ocaml::Error> {
+    let repr = x.as_ref().into_repr();
+    if len < 0 || (repr.num_bits() as isize) > len {
+        return Err(ocaml::Error::invalid_argument("caml_pasta_fp_to_bits")
+            .err()
+            .unwrap());
+    }
+    let num_bits = 64 * repr.0.len();
+    Ok((0..len as usize)
+        .map(|i| i < num_bits && repr.get_bit(i))
+        .collect())
+}
+
+/// Inverse of [caml_pasta_fp_to_bits]. Fails if the bits do not encode a canonical field element.
+#[ocaml::func]
+pub fn caml_pasta_fp_of_bits(bits: Vec<bool>) -> Result<Fp, ocaml::Error> {
+    let mut repr = BigInteger256::default();
+    for (i, bit) in bits.into_iter().enumerate() {
+        if !bit {
+            continue;
+        }
+        if i >= 64 * repr.0.len() {
+            return Err(ocaml::Error::invalid_argument("caml_pasta_fp_of_bits")
+                .err()
+                .unwrap());
+        }
+        repr.0[i / 64] |= 1 << (i % 64);
+    }
+    if repr >= Fp_params::MODULUS {
+        return Err(ocaml::Error::invalid_argument("caml_pasta_fp_of_bits")
+            .err()
+            .unwrap());
+    }
+    Ok(Fp::from_repr(repr))
+}
+
+#[ocaml::func]
+pub fn caml_pasta_fp_multiplicative_generator() -> Fp {
+    FftField::multiplicative_generator()
+}
+
+#[ocaml::func]
+pub fn caml_pasta_fp_two_adicity() -> ocaml::Int {
+    Fp_params::TWO_ADICITY as isize
+}
+
 #[ocaml::func]
 pub fn caml_pasta_fp_two_adic_root_of_unity() -> Fp {
     FftField::two_adic_root_of_unity()
@@ -194,3 +354,9 @@ pub fn caml_pasta_fp_of_bytes(x: &[u8]) -> Result<Fp, ocaml::Error> {
 pub fn caml_pasta_fp_deep_copy(x: Fp) -> Fp {
     x
 }
+
+/// Evaluates the `b` polynomial of each set of challenges at each of the `points`.
+#[ocaml::func]
+pub fn caml_pasta_fp_b_poly_evaluations(chals: Vec<Vec<Fp>>, points: Vec<Fp>) -> Vec<Vec<Fp>> {
+    crate::urs_utils::b_poly_evaluations(&chals, &points)
+}
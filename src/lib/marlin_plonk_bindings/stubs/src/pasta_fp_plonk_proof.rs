@@ -32,7 +32,7 @@ pub fn caml_pasta_fp_plonk_proof_create(
     auxiliary_input: CamlPastaFpVector,
     prev_challenges: Vec<Fp>,
     prev_sgs: Vec<GAffine>,
-) -> DlogProof<GAffine> {
+) -> Result<DlogProof<GAffine>, ocaml::Error> {
     // TODO: Should we be ignoring this?!
     let _primary_input = primary_input;
 
@@ -73,7 +73,13 @@ pub fn caml_pasta_fp_plonk_proof_create(
             DefaultFqSponge<VestaParameters, PlonkSpongeConstants>,
             DefaultFrSponge<Fp, PlonkSpongeConstants>,
         >(&map, auxiliary_input, index, prev)
-        .unwrap()
+    })
+    .map_err(|e| {
+        // Most likely the witness does not satisfy the circuit, which
+        // `caml_pasta_fp_plonk_gate_vector_check_witness` can diagnose.
+        ocaml::Error::failwith(format!("caml_pasta_fp_plonk_proof_create: {:?}", e))
+            .err()
+            .unwrap()
     })
 }
 
@@ -1,5 +1,7 @@
 use crate::caml_pointer;
+use crate::caml_urs::CamlUrs;
 use crate::index_serialization;
+use crate::plonk_circuit_diff;
 use crate::plonk_verifier_index::{
     CamlPlonkDomain, CamlPlonkVerificationEvals, CamlPlonkVerificationShifts,
     CamlPlonkVerifierIndex,
@@ -41,7 +43,7 @@ pub fn to_ocaml<'a>(
         },
         max_poly_size: vi.max_poly_size as isize,
         max_quot_size: vi.max_quot_size as isize,
-        urs: caml_pointer::create(Rc::clone(urs)),
+        urs: caml_pointer::create(CamlUrs::new(Rc::clone(urs))),
         evals: CamlPlonkVerificationEvals {
             sigma_comm0: sigma_comm0,
             sigma_comm1: sigma_comm1,
@@ -79,7 +81,7 @@ pub fn to_ocaml_copy<'a>(
         },
         max_poly_size: vi.max_poly_size as isize,
         max_quot_size: vi.max_quot_size as isize,
-        urs: caml_pointer::create(Rc::clone(urs)),
+        urs: caml_pointer::create(CamlUrs::new(Rc::clone(urs))),
         evals: CamlPlonkVerificationEvals {
             sigma_comm0: sigma_comm0.clone(),
             sigma_comm1: sigma_comm1.clone(),
@@ -269,7 +271,7 @@ pub fn caml_pasta_fp_plonk_verifier_index_dummy() -> CamlPastaFpPlonkVerifierInd
         },
         max_poly_size: 0,
         max_quot_size: 0,
-        urs: caml_pointer::create(Rc::new(SRS::create(0))),
+        urs: caml_pointer::create(CamlUrs::new(Rc::new(SRS::create(0)))),
         evals: CamlPlonkVerificationEvals {
             sigma_comm0: comm(),
             sigma_comm1: comm(),
@@ -303,3 +305,14 @@ pub fn caml_pasta_fp_plonk_verifier_index_deep_copy(
 ) -> CamlPastaFpPlonkVerifierIndex {
     x
 }
+
+/// The names of the fields of two verifier indices that differ. The URSs are not compared.
+#[ocaml::func]
+pub fn caml_pasta_fp_plonk_verifier_index_diff(
+    before: CamlPastaFpPlonkVerifierIndex,
+    after: CamlPastaFpPlonkVerifierIndex,
+) -> Vec<String> {
+    plonk_circuit_diff::verifier_index_diff(&before, &after, |x, y| {
+        x.unshifted == y.unshifted && x.shifted == y.shifted
+    })
+}
@@ -0,0 +1,212 @@
+use algebra::{
+    curves::{AffineCurve, ProjectiveCurve},
+    UniformRand, Zero,
+};
+use ff_fft::DensePolynomial;
+use mina_curves::pasta::{
+    fp::Fp,
+    vesta::{Affine as GAffine, VestaParameters},
+};
+
+use commitment_dlog::commitment::{CommitmentCurve, OpeningProof, PolyComm};
+use groupmap::GroupMap;
+use oracle::{poseidon::PlonkSpongeConstants, sponge::DefaultFqSponge, FqSponge};
+
+use crate::pasta_fp_urs::CamlPastaFpUrs;
+
+/* A general polynomial commitment interface over the URS. */
+
+type Sponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstants>;
+
+fn degree_bound(
+    name: &'static str,
+    max: Option<ocaml::Int>,
+) -> Result<Option<usize>, ocaml::Error> {
+    match max {
+        Some(n) if n <= 0 => Err(ocaml::Error::invalid_argument(name).err().unwrap()),
+        max => Ok(max.map(|n| n as usize)),
+    }
+}
+
+/// Evaluates each chunk of `chunk_size` coefficients of `coeffs` at `x`.
+fn chunked_evaluations(coeffs: &[Fp], chunk_size: usize, x: Fp) -> Vec<Fp> {
+    if coeffs.is_empty() {
+        return vec![Fp::zero()];
+    }
+    coeffs
+        .chunks(std::cmp::max(chunk_size, 1))
+        .map(|chunk| chunk.iter().rev().fold(Fp::zero(), |acc, c| acc * &x + c))
+        .collect()
+}
+
+fn blind(h: GAffine, comm: &PolyComm<GAffine>, blinders: &PolyComm<Fp>) -> PolyComm<GAffine> {
+    let blind = |g: &GAffine, r: &Fp| (g.into_projective() + &h.mul(*r)).into_affine();
+    PolyComm {
+        unshifted: comm
+            .unshifted
+            .iter()
+            .zip(blinders.unshifted.iter())
+            .map(|(g, r)| blind(g, r))
+            .collect(),
+        shifted: comm
+            .shifted
+            .as_ref()
+            .zip(blinders.shifted.as_ref())
+            .map(|(g, r)| blind(g, r)),
+    }
+}
+
+// The transcript starts from the commitments and the evaluation points, so that the prover and
+// the verifier derive the same challenges.
+fn transcript(comms: &[&PolyComm<GAffine>], points: &[Fp]) -> Sponge {
+    let mut sponge = Sponge::new(oracle::pasta::fq::params());
+    for comm in comms {
+        sponge.absorb_g(&comm.unshifted);
+        if let Some(shifted) = comm.shifted {
+            sponge.absorb_g(&[shifted]);
+        }
+    }
+    sponge.absorb_fr(points);
+    sponge
+}
+
+/// Commits to the polynomial with coefficients `coeffs`, shifting the commitment for the
+/// degree bound `max_degree` if given.
+#[ocaml::func]
+pub fn caml_pasta_fp_poly_comm_commit(
+    urs: CamlPastaFpUrs,
+    coeffs: Vec<Fp>,
+    max_degree: Option<ocaml::Int>,
+) -> Result<PolyComm<GAffine>, ocaml::Error> {
+    let max = degree_bound("caml_pasta_fp_poly_comm_commit", max_degree)?;
+    let p = DensePolynomial::<Fp>::from_coefficients_vec(coeffs);
+    Ok((*urs).commit_non_hiding(&p, max))
+}
+
+/// As `caml_pasta_fp_poly_comm_commit`, but adds `r h` to each part of the commitment for a
+/// fresh random `r`. Returns the commitment with the blinding factors of its parts, which
+/// `caml_pasta_fp_poly_comm_open` takes to open it.
+#[ocaml::func]
+pub fn caml_pasta_fp_poly_comm_commit_hiding(
+    urs: CamlPastaFpUrs,
+    coeffs: Vec<Fp>,
+    max_degree: Option<ocaml::Int>,
+) -> Result<(PolyComm<GAffine>, PolyComm<Fp>), ocaml::Error> {
+    let max = degree_bound("caml_pasta_fp_poly_comm_commit_hiding", max_degree)?;
+    let p = DensePolynomial::<Fp>::from_coefficients_vec(coeffs);
+    let comm = (*urs).commit_non_hiding(&p, max);
+    let rng = &mut rand_core::OsRng;
+    let blinders = PolyComm {
+        unshifted: comm.unshifted.iter().map(|_| Fp::rand(rng)).collect(),
+        shifted: comm.shifted.map(|_| Fp::rand(rng)),
+    };
+    Ok((blind((*urs).h, &comm, &blinders), blinders))
+}
+
+/// Evaluates the polynomial at `x`, split into chunks of the size of the URS as it is committed
+/// to. These are the evaluations that `caml_pasta_fp_poly_comm_verify` expects.
+#[ocaml::func]
+pub fn caml_pasta_fp_poly_comm_evaluate(urs: CamlPastaFpUrs, coeffs: Vec<Fp>, x: Fp) -> Vec<Fp> {
+    chunked_evaluations(&coeffs, (*urs).g.len(), x)
+}
+
+/// Creates a batched opening proof of the polynomials, with their optional degree bounds, at
+/// all of the `points`. `polyscale` and `evalscale` combine the polynomials and the points.
+/// Each polynomial comes with the blinding factors returned by
+/// `caml_pasta_fp_poly_comm_commit_hiding` if it was committed to with it, or `None` if it was
+/// committed to with `caml_pasta_fp_poly_comm_commit`.
+#[ocaml::func]
+pub fn caml_pasta_fp_poly_comm_open(
+    urs: CamlPastaFpUrs,
+    polys: Vec<(Vec<Fp>, Option<ocaml::Int>, Option<PolyComm<Fp>>)>,
+    points: Vec<Fp>,
+    (polyscale, evalscale): (Fp, Fp),
+) -> Result<OpeningProof<GAffine>, ocaml::Error> {
+    let mut plnms = Vec::with_capacity(polys.len());
+    let mut comms = Vec::with_capacity(polys.len());
+    for (coeffs, max_degree, blinders) in polys.into_iter() {
+        let max = degree_bound("caml_pasta_fp_poly_comm_open", max_degree)?;
+        let p = DensePolynomial::<Fp>::from_coefficients_vec(coeffs);
+        let comm = (*urs).commit_non_hiding(&p, max);
+        let blinders = match blinders {
+            None => PolyComm {
+                unshifted: vec![Fp::zero(); comm.unshifted.len()],
+                shifted: comm.shifted.map(|_| Fp::zero()),
+            },
+            Some(b)
+                if b.unshifted.len() == comm.unshifted.len()
+                    && b.shifted.is_some() == comm.shifted.is_some() =>
+            {
+                b
+            }
+            // The blinding factors are for a commitment of another shape.
+            Some(_) => {
+                return Err(
+                    ocaml::Error::invalid_argument("caml_pasta_fp_poly_comm_open")
+                        .err()
+                        .unwrap(),
+                )
+            }
+        };
+        // The transcript absorbs the commitments as the verifier sees them.
+        comms.push(blind((*urs).h, &comm, &blinders));
+        plnms.push((p, max, blinders));
+    }
+    let sponge = transcript(&comms.iter().collect::<Vec<_>>(), &points);
+
+    let group_map = <GAffine as CommitmentCurve>::Map::setup();
+    Ok((*urs).open::<Sponge>(
+        &group_map,
+        plnms
+            .iter()
+            .map(|(p, max, blinders)| (p, *max, blinders.clone()))
+            .collect(),
+        &points,
+        polyscale,
+        evalscale,
+        sponge,
+        &mut rand_core::OsRng,
+    ))
+}
+
+/// Verifies an opening proof made by `caml_pasta_fp_poly_comm_open`. Each commitment comes with
+/// its degree bound and its chunked evaluations at each of the `points`.
+#[ocaml::func]
+pub fn caml_pasta_fp_poly_comm_verify(
+    urs: CamlPastaFpUrs,
+    comms: Vec<(PolyComm<GAffine>, Option<ocaml::Int>, Vec<Vec<Fp>>)>,
+    points: Vec<Fp>,
+    (polyscale, evalscale): (Fp, Fp),
+    proof: OpeningProof<GAffine>,
+) -> Result<bool, ocaml::Error> {
+    if comms
+        .iter()
+        .any(|(_, _, evals)| evals.len() != points.len())
+    {
+        return Err(
+            ocaml::Error::invalid_argument("caml_pasta_fp_poly_comm_verify")
+                .err()
+                .unwrap(),
+        );
+    }
+    let mut bounds = Vec::with_capacity(comms.len());
+    for (_, max_degree, _) in comms.iter() {
+        bounds.push(degree_bound("caml_pasta_fp_poly_comm_verify", *max_degree)?);
+    }
+    let sponge = transcript(
+        &comms.iter().map(|(comm, _, _)| comm).collect::<Vec<_>>(),
+        &points,
+    );
+
+    let group_map = <GAffine as CommitmentCurve>::Map::setup();
+    let polys = comms
+        .iter()
+        .zip(bounds.into_iter())
+        .map(|((comm, _, evals), max)| (comm, evals.iter().collect(), max))
+        .collect();
+    Ok((*urs).verify::<Sponge>(
+        &group_map,
+        &mut vec![(sponge, points.clone(), polyscale, evalscale, polys, &proof)],
+        &mut rand_core::OsRng,
+    ))
+}
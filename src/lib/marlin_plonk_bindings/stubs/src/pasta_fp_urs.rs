@@ -9,17 +9,92 @@ use commitment_dlog::{
 
 use std::{
     fs::{File, OpenOptions},
-    io::{BufReader, BufWriter, Seek, SeekFrom::Start},
+    io::{BufReader, BufWriter, Seek, SeekFrom::Start, Write},
+    path::Path,
     rc::Rc,
 };
 
+use crate::caml_bytes;
 use crate::caml_pointer::{self, CamlPointer};
+use crate::caml_urs::CamlUrs;
+use crate::pasta_rng::CamlPastaRngPtr;
+use crate::pasta_vesta::{affine_of_uncompressed, affine_to_uncompressed};
+use crate::urs_container::{self, UrsCurve};
+use crate::urs_lagrange_basis;
+use crate::urs_mmap::{self, MappedUrs};
 
-pub type CamlPastaFpUrs = CamlPointer<Rc<SRS<GAffine>>>;
+pub type CamlPastaFpUrs = CamlPointer<CamlUrs<GAffine>>;
 
 #[ocaml::func]
 pub fn caml_pasta_fp_urs_create(depth: ocaml::Int) -> CamlPastaFpUrs {
-    caml_pointer::create(Rc::new(SRS::create(depth as usize)))
+    caml_pointer::create(CamlUrs::new(Rc::new(SRS::create(depth as usize))))
+}
+
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_depth(urs: CamlPastaFpUrs) -> ocaml::Int {
+    urs.g.len() as ocaml::Int
+}
+
+/// Returns a URS of the given depth that agrees with `urs` on their common prefix, deriving only
+/// the points that `urs` is missing. Fails if `urs` was not derived by `SRS::create`.
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_extend(
+    urs: CamlPastaFpUrs,
+    depth: ocaml::Int,
+) -> Result<CamlPastaFpUrs, ocaml::Error> {
+    if depth < 0 {
+        return Err(ocaml::Error::invalid_argument("caml_pasta_fp_urs_extend")
+            .err()
+            .unwrap());
+    }
+    let srs = crate::urs_utils::urs_extend(&*urs, depth as usize).ok_or_else(|| {
+        ocaml::Error::failwith("urs_extend: URS was not derived by SRS::create")
+            .err()
+            .unwrap()
+    })?;
+    Ok(caml_pointer::create(CamlUrs::new(Rc::new(srs))))
+}
+
+/// Returns the URS made of the first `depth` points of `urs`.
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_trim(
+    urs: CamlPastaFpUrs,
+    depth: ocaml::Int,
+) -> Result<CamlPastaFpUrs, ocaml::Error> {
+    if depth < 0 || depth as usize > urs.g.len() {
+        return Err(ocaml::Error::invalid_argument("caml_pasta_fp_urs_trim")
+            .err()
+            .unwrap());
+    }
+    let srs = crate::urs_utils::urs_trim(&*urs, depth as usize);
+    Ok(caml_pointer::create(CamlUrs::new(Rc::new(srs))))
+}
+
+/// A digest of all of the points of the URS, to check that two URSs are the same.
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_digest(urs: CamlPastaFpUrs) -> ocaml::Value {
+    caml_bytes::of_slice(&crate::urs_utils::urs_digest(&*urs))
+}
+
+/// Checks that the URS was derived by `caml_pasta_fp_urs_create`, on all of its points or on a
+/// random sample of `sample_size` of them.
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_verify_derivation(
+    sample_size: Option<ocaml::Int>,
+    urs: CamlPastaFpUrs,
+) -> Result<bool, ocaml::Error> {
+    let sample_size = match sample_size {
+        Some(n) if n < 0 => {
+            return Err(
+                ocaml::Error::invalid_argument("caml_pasta_fp_urs_verify_derivation")
+                    .err()
+                    .unwrap(),
+            )
+        }
+        n => n.map(|n| n as usize),
+    };
+    crate::urs_utils::urs_verify_derivation(&*urs, sample_size)
+        .map_err(|e| ocaml::Error::failwith(&e).err().unwrap())
 }
 
 #[ocaml::func]
@@ -28,14 +103,22 @@ pub fn caml_pasta_fp_urs_write(
     urs: CamlPastaFpUrs,
     path: String,
 ) -> Result<(), ocaml::Error> {
-    match OpenOptions::new().append(append.unwrap_or(true)).open(path) {
+    let append = append.unwrap_or(true);
+    match OpenOptions::new()
+        .write(true)
+        .create(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)
+    {
         Err(_) => Err(ocaml::Error::invalid_argument("caml_pasta_fp_urs_write")
             .err()
             .unwrap()),
         Ok(file) => {
-            let file = BufWriter::new(file);
+            let mut file = BufWriter::new(file);
             let urs: &SRS<GAffine> = &*urs;
-            let _ = (*urs).write(file);
+            (*urs).write(&mut file)?;
+            file.flush()?;
             Ok(())
         }
     }
@@ -60,12 +143,141 @@ pub fn caml_pasta_fp_urs_read(
             };
             match SRS::<GAffine>::read(file) {
                 Err(_) => Ok(None),
-                Ok(urs) => Ok(Some(caml_pointer::create(Rc::new(urs)))),
+                Ok(urs) => Ok(Some(caml_pointer::create(CamlUrs::new(Rc::new(urs))))),
             }
         }
     }
 }
 
+/// Writes the URS to `path` in the checksummed container format, replacing any existing file
+/// atomically.
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_save(urs: CamlPastaFpUrs, path: String) -> Result<(), ocaml::Error> {
+    urs_container::write(&*urs, UrsCurve::Vesta, Path::new(&path))?;
+    Ok(())
+}
+
+/// Reads a URS written by `caml_pasta_fp_urs_save`, failing with a description of the problem
+/// if the file is for the wrong curve, truncated or corrupted.
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_load(path: String) -> Result<CamlPastaFpUrs, ocaml::Error> {
+    let urs = urs_container::read::<GAffine>(UrsCurve::Vesta, Path::new(&path))?;
+    Ok(caml_pointer::create(CamlUrs::new(Rc::new(urs))))
+}
+
+/// Writes the URS to `path` in a layout that can be memory-mapped with
+/// `caml_pasta_fp_urs_map`.
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_save_mapped(
+    urs: CamlPastaFpUrs,
+    path: String,
+) -> Result<(), ocaml::Error> {
+    urs_mmap::write(
+        &*urs,
+        UrsCurve::Vesta,
+        Path::new(&path),
+        affine_to_uncompressed,
+    )?;
+    Ok(())
+}
+
+pub struct CamlPastaFpMappedUrs(pub MappedUrs<GAffine>);
+pub type CamlPastaFpMappedUrsPtr<'a> = ocaml::Pointer<'a, CamlPastaFpMappedUrs>;
+
+extern "C" fn caml_pasta_fp_mapped_urs_finalize(v: ocaml::Raw) {
+    unsafe {
+        let v: CamlPastaFpMappedUrsPtr = v.as_pointer();
+        v.drop_in_place()
+    };
+}
+
+ocaml::custom!(CamlPastaFpMappedUrs {
+    finalize: caml_pasta_fp_mapped_urs_finalize,
+});
+
+/// Memory-maps a URS written by `caml_pasta_fp_urs_save_mapped`. Points are only decoded when
+/// they are used, and the mapped pages are shared between processes. Every point is checked in
+/// the background.
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_map(path: String) -> Result<CamlPastaFpMappedUrs, ocaml::Error> {
+    Ok(CamlPastaFpMappedUrs(MappedUrs::open(
+        UrsCurve::Vesta,
+        Path::new(&path),
+        affine_of_uncompressed,
+    )?))
+}
+
+#[ocaml::func]
+pub fn caml_pasta_fp_mapped_urs_depth(urs: CamlPastaFpMappedUrsPtr) -> ocaml::Int {
+    urs.as_ref().0.depth() as ocaml::Int
+}
+
+#[ocaml::func]
+pub fn caml_pasta_fp_mapped_urs_g(
+    urs: CamlPastaFpMappedUrsPtr,
+    i: ocaml::Int,
+) -> Result<GAffine, ocaml::Error> {
+    let g = if i < 0 {
+        None
+    } else {
+        urs.as_ref().0.g(i as usize)
+    };
+    match g {
+        None => Err(ocaml::Error::invalid_argument("caml_pasta_fp_mapped_urs_g")
+            .err()
+            .unwrap()),
+        Some(g) => Ok(g?),
+    }
+}
+
+#[ocaml::func]
+pub fn caml_pasta_fp_mapped_urs_h(urs: CamlPastaFpMappedUrsPtr) -> Result<GAffine, ocaml::Error> {
+    Ok(urs.as_ref().0.h()?)
+}
+
+/// Whether every point of the URS is valid, or `None` while they are still being checked.
+#[ocaml::func]
+pub fn caml_pasta_fp_mapped_urs_checked(urs: CamlPastaFpMappedUrsPtr) -> Option<bool> {
+    urs.as_ref().0.checked().map(|result| result.is_ok())
+}
+
+/// Waits until every point of the URS has been checked, failing with the first invalid one.
+#[ocaml::func]
+pub fn caml_pasta_fp_mapped_urs_wait_checked(
+    urs: CamlPastaFpMappedUrsPtr,
+) -> Result<(), ocaml::Error> {
+    Ok(urs.as_ref().0.wait_checked()?)
+}
+
+/// Commits to the polynomial with coefficients `coeffs`, as `caml_pasta_fp_poly_comm_commit`
+/// does without a degree bound, decoding only the points it needs.
+#[ocaml::func]
+pub fn caml_pasta_fp_mapped_urs_commit(
+    urs: CamlPastaFpMappedUrsPtr,
+    coeffs: Vec<Fp>,
+) -> Result<PolyComm<GAffine>, ocaml::Error> {
+    let urs = &urs.as_ref().0;
+    if coeffs.len() > urs.depth() {
+        return Err(
+            ocaml::Error::invalid_argument("caml_pasta_fp_mapped_urs_commit")
+                .err()
+                .unwrap(),
+        );
+    }
+    let srs = urs.prefix(std::cmp::max(coeffs.len(), 1))?;
+    let p = DensePolynomial::<Fp>::from_coefficients_vec(coeffs);
+    Ok(srs.commit_non_hiding(&p, None))
+}
+
+/// Decodes all of the points of a mapped URS in parallel, as needed to create an index.
+#[ocaml::func]
+pub fn caml_pasta_fp_mapped_urs_to_urs(
+    urs: CamlPastaFpMappedUrsPtr,
+) -> Result<CamlPastaFpUrs, ocaml::Error> {
+    let srs = urs.as_ref().0.to_srs()?;
+    Ok(caml_pointer::create(CamlUrs::new(srs)))
+}
+
 #[ocaml::func]
 pub fn caml_pasta_fp_urs_lagrange_commitment(
     urs: CamlPastaFpUrs,
@@ -79,6 +291,13 @@ pub fn caml_pasta_fp_urs_lagrange_commitment(
                 .unwrap(),
         ),
         Some(x_domain) => {
+            if let Some(basis) = urs.cached_lagrange_basis(domain_size as usize) {
+                return basis.get(i as usize).cloned().ok_or_else(|| {
+                    ocaml::Error::invalid_argument("caml_pasta_fp_urs_lagrange_commitment")
+                        .err()
+                        .unwrap()
+                });
+            }
             let evals = (0..domain_size)
                 .map(|j| if i == j { Fp::one() } else { Fp::zero() })
                 .collect();
@@ -88,6 +307,59 @@ pub fn caml_pasta_fp_urs_lagrange_commitment(
     }
 }
 
+/// Computes the commitments to all of the Lagrange basis polynomials of the domain at once, with
+/// an inverse FFT over the URS. The result is cached in the URS, and later calls to
+/// `caml_pasta_fp_urs_lagrange_commitment` for the same domain use the cache.
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_lagrange_commitments(
+    urs: CamlPastaFpUrs,
+    domain_size: ocaml::Int,
+) -> Result<Vec<PolyComm<GAffine>>, ocaml::Error> {
+    let basis = if domain_size <= 0 {
+        None
+    } else {
+        urs.lagrange_basis(domain_size as usize)
+    };
+    match basis {
+        None => Err(
+            ocaml::Error::invalid_argument("caml_pasta_fp_urs_lagrange_commitments")
+                .err()
+                .unwrap(),
+        ),
+        Some(basis) => Ok((*basis).clone()),
+    }
+}
+
+/// Writes the cached Lagrange basis commitments to `path`, to be restored with
+/// `caml_pasta_fp_urs_read_lagrange_bases`.
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_write_lagrange_bases(
+    urs: CamlPastaFpUrs,
+    path: String,
+) -> Result<(), ocaml::Error> {
+    urs_lagrange_basis::write(
+        &*urs,
+        UrsCurve::Vesta,
+        Path::new(&path),
+        affine_to_uncompressed,
+    )?;
+    Ok(())
+}
+
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_read_lagrange_bases(
+    urs: CamlPastaFpUrs,
+    path: String,
+) -> Result<(), ocaml::Error> {
+    urs_lagrange_basis::read(
+        &*urs,
+        UrsCurve::Vesta,
+        Path::new(&path),
+        affine_of_uncompressed,
+    )?;
+    Ok(())
+}
+
 #[ocaml::func]
 pub fn caml_pasta_fp_urs_commit_evaluations(
     urs: CamlPastaFpUrs,
@@ -119,30 +391,114 @@ pub fn caml_pasta_fp_urs_b_poly_commitment(
     Ok((*urs).commit_non_hiding(&p, None).into())
 }
 
+/// Commits to the `b` polynomial of each set of challenges, in parallel.
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_b_poly_commitments(
+    urs: CamlPastaFpUrs,
+    chals: Vec<Vec<Fp>>,
+) -> Vec<PolyComm<GAffine>> {
+    crate::urs_utils::b_poly_commitments(&*urs, &chals)
+}
+
+fn accumulator_rounds(
+    name: &'static str,
+    rounds: Option<Vec<ocaml::Int>>,
+) -> Result<Option<Vec<usize>>, ocaml::Error> {
+    match rounds {
+        Some(rounds) if rounds.iter().any(|r| *r < 0) => {
+            Err(ocaml::Error::invalid_argument(name).err().unwrap())
+        }
+        rounds => Ok(rounds.map(|rounds| rounds.into_iter().map(|r| r as usize).collect())),
+    }
+}
+
+/// Checks the accumulators `comms` against their challenges `chals`, concatenated. `rounds`
+/// gives the number of challenges of each accumulator; by default they all have the same number.
+/// The random combination is drawn from `rng` if given, and from the OS otherwise.
 #[ocaml::func]
 pub fn caml_pasta_fp_urs_batch_accumulator_check(
+    rng: Option<CamlPastaRngPtr>,
+    rounds: Option<Vec<ocaml::Int>>,
     urs: CamlPastaFpUrs,
     comms: Vec<GAffine>,
     chals: Vec<Fp>,
-) -> bool {
-    crate::urs_utils::batch_dlog_accumulator_check(
-        &*urs,
-        &comms.into_iter().map(From::from).collect(),
-        &chals.into_iter().map(From::from).collect(),
-    )
+) -> Result<bool, ocaml::Error> {
+    let rounds = accumulator_rounds("caml_pasta_fp_urs_batch_accumulator_check", rounds)?;
+    let rounds = rounds.as_deref();
+    let comms = comms.into_iter().map(From::from).collect();
+    let chals = chals.into_iter().map(From::from).collect();
+    Ok(match rng {
+        Some(mut rng) => crate::urs_utils::batch_dlog_accumulator_check(
+            &*urs,
+            &comms,
+            rounds,
+            &chals,
+            rng.as_mut().rng(),
+        ),
+        None => crate::urs_utils::batch_dlog_accumulator_check(
+            &*urs,
+            &comms,
+            rounds,
+            &chals,
+            &mut rand_core::OsRng,
+        ),
+    }?)
+}
+
+/// As `caml_pasta_fp_urs_batch_accumulator_check`, but returns the indices of the accumulators
+/// that fail, in increasing order.
+#[ocaml::func]
+pub fn caml_pasta_fp_urs_batch_accumulator_failures(
+    rng: Option<CamlPastaRngPtr>,
+    rounds: Option<Vec<ocaml::Int>>,
+    urs: CamlPastaFpUrs,
+    comms: Vec<GAffine>,
+    chals: Vec<Fp>,
+) -> Result<Vec<ocaml::Int>, ocaml::Error> {
+    let rounds = accumulator_rounds("caml_pasta_fp_urs_batch_accumulator_failures", rounds)?;
+    let rounds = rounds.as_deref();
+    let comms = comms.into_iter().map(From::from).collect();
+    let chals = chals.into_iter().map(From::from).collect();
+    let failures = match rng {
+        Some(mut rng) => crate::urs_utils::batch_dlog_accumulator_failures(
+            &*urs,
+            &comms,
+            rounds,
+            &chals,
+            rng.as_mut().rng(),
+        ),
+        None => crate::urs_utils::batch_dlog_accumulator_failures(
+            &*urs,
+            &comms,
+            rounds,
+            &chals,
+            &mut rand_core::OsRng,
+        ),
+    }?;
+    Ok(failures.into_iter().map(|i| i as ocaml::Int).collect())
 }
 
 #[ocaml::func]
 pub fn caml_pasta_fp_urs_batch_accumulator_generate(
+    rounds: Option<Vec<ocaml::Int>>,
     urs: CamlPastaFpUrs,
     comms: ocaml::Int,
     chals: Vec<Fp>,
-) -> Vec<GAffine> {
-    crate::urs_utils::batch_dlog_accumulator_generate(
+) -> Result<Vec<GAffine>, ocaml::Error> {
+    if comms < 0 {
+        return Err(
+            ocaml::Error::invalid_argument("caml_pasta_fp_urs_batch_accumulator_generate")
+                .err()
+                .unwrap(),
+        );
+    }
+    let rounds = accumulator_rounds("caml_pasta_fp_urs_batch_accumulator_generate", rounds)?;
+    Ok(crate::urs_utils::batch_dlog_accumulator_generate(
         &*urs,
         comms as usize,
+        rounds.as_deref(),
         &chals.into_iter().map(From::from).collect(),
-    )
+    )?)
 }
 
 #[ocaml::func]
@@ -15,16 +15,28 @@ use commitment_dlog::srs::{SRS, SRSSpec};
 use plonk_protocol_dlog::index::Index as DlogIndex;
 
 use std::{
+    convert::TryFrom,
     fs::{File, OpenOptions},
     io::{BufReader, BufWriter, Seek, SeekFrom::Start},
+    path::Path,
     rc::Rc,
 };
 
+use crate::caml_bytes;
 use crate::index_serialization;
-use crate::plonk_gate::{CamlPlonkCol, CamlPlonkGate, CamlPlonkWire};
+use crate::plonk_gate::{CamlPlonkGate, CamlPlonkWire};
+use crate::plonk_gate_export::{self, CamlPlonkRowSelection};
+use crate::plonk_circuit_diff::{self, CamlPlonkCircuitDiff};
+use crate::plonk_circuit_digest;
+use crate::plonk_circuit_stats::{self, CamlPlonkCircuitStats};
+use crate::plonk_wiring::Wiring;
+use crate::plonk_witness_check::{self, CamlWitnessReport};
+use crate::pasta_fq_vector::CamlPastaFqVector;
 use crate::pasta_fq_urs::CamlPastaFqUrs;
 
-pub struct CamlPastaFqPlonkGateVector(Vec<Gate<Fq>>);
+/* The gates of a circuit, with the copy constraints declared between their wires. */
+
+pub struct CamlPastaFqPlonkGateVector(Vec<Gate<Fq>>, Wiring);
 pub type CamlPastaFqPlonkGateVectorPtr<'a> = ocaml::Pointer<'a, CamlPastaFqPlonkGateVector>;
 
 extern "C" fn caml_pasta_fq_plonk_gate_vector_finalize(v: ocaml::Raw) {
@@ -40,7 +52,7 @@ ocaml::custom!(CamlPastaFqPlonkGateVector {
 
 #[ocaml::func]
 pub fn caml_pasta_fq_plonk_gate_vector_create() -> CamlPastaFqPlonkGateVector {
-    CamlPastaFqPlonkGateVector(Vec::new())
+    CamlPastaFqPlonkGateVector(Vec::new(), Wiring::new())
 }
 
 #[ocaml::func]
@@ -59,14 +71,32 @@ pub fn caml_pasta_fq_plonk_gate_vector_add(
 pub fn caml_pasta_fq_plonk_gate_vector_get(
     v: CamlPastaFqPlonkGateVectorPtr,
     i: ocaml::Int,
-) -> CamlPlonkGate<Vec<Fq>> {
-    let gate = &(v.as_ref().0)[i as usize];
+) -> Result<CamlPlonkGate<Vec<Fq>>, ocaml::Error> {
+    let gate = match usize::try_from(i).ok().and_then(|i| v.as_ref().0.get(i)) {
+        None => Err(
+            ocaml::Error::invalid_argument("caml_pasta_fq_plonk_gate_vector_get")
+                .err()
+                .unwrap(),
+        )?,
+        Some(gate) => gate,
+    };
     let c = gate.c.iter().map(|x| *x).collect();
-    CamlPlonkGate {
+    Ok(CamlPlonkGate {
         typ: (&gate.typ).into(),
         wires: (&gate.wires).into(),
         c,
+    })
+}
+
+fn wire_in_range(
+    name: &'static str,
+    w: &CamlPlonkWire,
+    rows: usize,
+) -> Result<Wire, ocaml::Error> {
+    if w.row < 0 || w.row as usize >= rows {
+        return Err(ocaml::Error::invalid_argument(name).err().unwrap());
     }
+    Ok(w.into())
 }
 
 #[ocaml::func]
@@ -74,27 +104,228 @@ pub fn caml_pasta_fq_plonk_gate_vector_wrap(
     mut v: CamlPastaFqPlonkGateVectorPtr,
     t: CamlPlonkWire,
     h: CamlPlonkWire,
-) {
+) -> Result<(), ocaml::Error> {
+    // The head may refer to a gate that has not been added yet, but the tail must exist.
+    let rows = v.as_ref().0.len();
+    let t = wire_in_range("caml_pasta_fq_plonk_gate_vector_wrap", &t, rows)?;
+    let h = wire_in_range("caml_pasta_fq_plonk_gate_vector_wrap", &h, usize::MAX)?;
+    let wires = &mut (v.as_mut().0)[t.row].wires;
     match t.col {
-        CamlPlonkCol::L => {
-            (v.as_mut().0)[t.row as usize].wires.l = Wire {
-                row: h.row as usize,
-                col: h.col.into(),
-            }
-        }
-        CamlPlonkCol::R => {
-            (v.as_mut().0)[t.row as usize].wires.r = Wire {
-                row: h.row as usize,
-                col: h.col.into(),
-            }
-        }
-        CamlPlonkCol::O => {
-            (v.as_mut().0)[t.row as usize].wires.o = Wire {
-                row: h.row as usize,
-                col: h.col.into(),
+        L => wires.l = h,
+        R => wires.r = h,
+        O => wires.o = h,
+    }
+    Ok(())
+}
+
+/// Declares that the wires `a` and `b` hold the same variable. The permutation cycles are
+/// computed from these declarations when the index is created, and override any wiring made by
+/// `caml_pasta_fq_plonk_gate_vector_wrap` at the same positions.
+#[ocaml::func]
+pub fn caml_pasta_fq_plonk_gate_vector_connect(
+    mut v: CamlPastaFqPlonkGateVectorPtr,
+    a: CamlPlonkWire,
+    b: CamlPlonkWire,
+) -> Result<(), ocaml::Error> {
+    // The rows are checked against the number of gates once the circuit is finalised.
+    let a = wire_in_range("caml_pasta_fq_plonk_gate_vector_connect", &a, usize::MAX)?;
+    let b = wire_in_range("caml_pasta_fq_plonk_gate_vector_connect", &b, usize::MAX)?;
+    v.as_mut().1.connect(&a, &b);
+    Ok(())
+}
+
+/// Rewires the gates according to the wires connected so far. This is done when the index is
+/// created, and only needs to be called to inspect the wiring beforehand.
+#[ocaml::func]
+pub fn caml_pasta_fq_plonk_gate_vector_apply_wiring(
+    mut v: CamlPastaFqPlonkGateVectorPtr,
+) -> Result<(), ocaml::Error> {
+    let CamlPastaFqPlonkGateVector(gates, wiring) = v.as_mut();
+    Ok(wiring.apply(gates)?)
+}
+
+/// Checks `witness`, laid out as for `caml_pasta_fq_plonk_proof_create`, against the gates and
+/// their wiring. Reports the first `limit` failing rows, and the number of failing rows.
+#[ocaml::func]
+pub fn caml_pasta_fq_plonk_gate_vector_check_witness(
+    mut v: CamlPastaFqPlonkGateVectorPtr,
+    public: ocaml::Int,
+    witness: CamlPastaFqVector,
+    limit: ocaml::Int,
+) -> Result<CamlWitnessReport<Fq>, ocaml::Error> {
+    if public < 0 || limit < 0 {
+        return Err(
+            ocaml::Error::invalid_argument("caml_pasta_fq_plonk_gate_vector_check_witness")
+                .err()
+                .unwrap(),
+        );
+    }
+    let CamlPastaFqPlonkGateVector(gates, wiring) = v.as_mut();
+    wiring.apply(gates)?;
+    let cs = constraint_system(
+        "caml_pasta_fq_plonk_gate_vector_check_witness",
+        gates,
+        public as usize,
+    )?;
+    Ok(plonk_witness_check::check(
+        gates,
+        public as usize,
+        &*witness,
+        limit as usize,
+        |row| cs.gates[row].verify(&*witness, &cs),
+    )?)
+}
+
+/// Writes the gates and witness values of the rows from `first` to `last` inclusive to `path`,
+/// as CSV.
+#[ocaml::func]
+pub fn caml_pasta_fq_plonk_gate_vector_write_witness_csv(
+    mut v: CamlPastaFqPlonkGateVectorPtr,
+    witness: CamlPastaFqVector,
+    first: ocaml::Int,
+    last: ocaml::Int,
+    path: String,
+) -> Result<(), ocaml::Error> {
+    if first < 0 || last < first {
+        return Err(
+            ocaml::Error::invalid_argument("caml_pasta_fq_plonk_gate_vector_write_witness_csv")
+                .err()
+                .unwrap(),
+        );
+    }
+    let CamlPastaFqPlonkGateVector(gates, wiring) = v.as_mut();
+    wiring.apply(gates)?;
+    Ok(plonk_witness_check::write_csv(
+        gates,
+        &*witness,
+        first as usize..last as usize + 1,
+        Path::new(&path),
+    )?)
+}
+
+/// The shape of the circuit, as `caml_pasta_fq_plonk_index_create` would build it with `public`
+/// public inputs. The URS, if given, is compared with the depth the circuit needs.
+#[ocaml::func]
+pub fn caml_pasta_fq_plonk_gate_vector_stats(
+    mut v: CamlPastaFqPlonkGateVectorPtr,
+    public: ocaml::Int,
+    urs: Option<CamlPastaFqUrs>,
+) -> Result<CamlPlonkCircuitStats, ocaml::Error> {
+    if public < 0 {
+        return Err(
+            ocaml::Error::invalid_argument("caml_pasta_fq_plonk_gate_vector_stats")
+                .err()
+                .unwrap(),
+        );
+    }
+    let CamlPastaFqPlonkGateVector(gates, wiring) = v.as_mut();
+    wiring.apply(gates)?;
+    let n = plonk_witness_check::domain_size::<Fq>(gates.len())?;
+    let mut succ: Vec<Option<usize>> = (0..3 * n).map(Some).collect();
+    for gate in gates.iter() {
+        for (col, target) in [(L, &gate.wires.l), (R, &gate.wires.r), (O, &gate.wires.o)].iter() {
+            let p = plonk_witness_check::position(n, &Wire { row: gate.wires.row, col: *col });
+            if p < succ.len() {
+                succ[p] = if target.row < n {
+                    Some(plonk_witness_check::position(n, target))
+                } else {
+                    None
+                };
             }
         }
     }
+    // Trailing padding is left out, as the index adds its own.
+    let canonical = plonk_circuit_digest::of_gates(gates);
+    let gates = plonk_circuit_digest::without_padding(&canonical);
+    Ok(plonk_circuit_stats::stats(
+        plonk_circuit_stats::Shape {
+            rows: gates.len(),
+            public: public as usize,
+            d1: n,
+            d4: 4 * n,
+            d8: 8 * n,
+            urs_depth: urs.map(|urs| urs.g.len()),
+        },
+        plonk_circuit_stats::gate_counts(gates.iter().map(|gate| gate.typ)),
+        plonk_circuit_stats::cycles(3 * n, |p| succ[p]),
+    ))
+}
+
+/// A digest of the circuit, after applying its wiring, that matches the digest of an index
+/// created from it with `public` public inputs.
+#[ocaml::func]
+pub fn caml_pasta_fq_plonk_gate_vector_digest(
+    mut v: CamlPastaFqPlonkGateVectorPtr,
+    public: ocaml::Int,
+) -> Result<ocaml::Value, ocaml::Error> {
+    if public < 0 {
+        return Err(
+            ocaml::Error::invalid_argument("caml_pasta_fq_plonk_gate_vector_digest")
+                .err()
+                .unwrap(),
+        );
+    }
+    let CamlPastaFqPlonkGateVector(gates, wiring) = v.as_mut();
+    wiring.apply(gates)?;
+    let n = plonk_witness_check::domain_size::<Fq>(gates.len())?;
+    let gates = plonk_circuit_digest::of_gates(gates);
+    Ok(caml_bytes::of_slice(&plonk_circuit_digest::digest(public as usize, n, &gates)))
+}
+
+/// Compares the gates of two circuits row by row, after applying their wiring.
+#[ocaml::func]
+pub fn caml_pasta_fq_plonk_gate_vector_diff(
+    mut before: CamlPastaFqPlonkGateVectorPtr,
+    mut after: CamlPastaFqPlonkGateVectorPtr,
+) -> Result<CamlPlonkCircuitDiff<Fq>, ocaml::Error> {
+    {
+        let CamlPastaFqPlonkGateVector(gates, wiring) = before.as_mut();
+        wiring.apply(gates)?;
+    }
+    {
+        let CamlPastaFqPlonkGateVector(gates, wiring) = after.as_mut();
+        wiring.apply(gates)?;
+    }
+    Ok(plonk_circuit_diff::diff(
+        None,
+        &plonk_circuit_digest::of_gates(&before.as_ref().0),
+        &plonk_circuit_digest::of_gates(&after.as_ref().0),
+    ))
+}
+
+/// Writes the gates in `rows`, or all of them, after applying their wiring, as JSON.
+#[ocaml::func]
+pub fn caml_pasta_fq_plonk_gate_vector_write_json(
+    rows: Option<CamlPlonkRowSelection>,
+    mut v: CamlPastaFqPlonkGateVectorPtr,
+    path: String,
+) -> Result<(), ocaml::Error> {
+    let CamlPastaFqPlonkGateVector(gates, wiring) = v.as_mut();
+    wiring.apply(gates)?;
+    let rows = plonk_gate_export::select_rows(gates, rows.as_ref()).ok_or_else(|| {
+        ocaml::Error::invalid_argument("caml_pasta_fq_plonk_gate_vector_write_json")
+            .err()
+            .unwrap()
+    })?;
+    Ok(plonk_gate_export::write_json(gates, &rows, Path::new(&path))?)
+}
+
+/// Writes the gates in `rows`, or all of them, after applying their wiring, as a Graphviz DOT
+/// graph of their copy constraints.
+#[ocaml::func]
+pub fn caml_pasta_fq_plonk_gate_vector_write_dot(
+    rows: Option<CamlPlonkRowSelection>,
+    mut v: CamlPastaFqPlonkGateVectorPtr,
+    path: String,
+) -> Result<(), ocaml::Error> {
+    let CamlPastaFqPlonkGateVector(gates, wiring) = v.as_mut();
+    wiring.apply(gates)?;
+    let rows = plonk_gate_export::select_rows(gates, rows.as_ref()).ok_or_else(|| {
+        ocaml::Error::invalid_argument("caml_pasta_fq_plonk_gate_vector_write_dot")
+            .err()
+            .unwrap()
+    })?;
+    Ok(plonk_gate_export::write_dot(gates, &rows, Path::new(&path))?)
 }
 
 /* Boxed so that we don't store large proving indexes in the OCaml heap. */
@@ -113,18 +344,15 @@ ocaml::custom!(CamlPastaFqPlonkIndex<'a> {
     finalize: caml_pasta_fq_plonk_index_finalize,
 });
 
-#[ocaml::func]
-pub fn caml_pasta_fq_plonk_index_create(
-    gates: CamlPastaFqPlonkGateVectorPtr,
-    public: ocaml::Int,
-    urs: CamlPastaFqUrs,
-) -> Result<CamlPastaFqPlonkIndex<'static>, ocaml::Error> {
-    let n = match Domain::<Fq>::compute_size_of_domain(gates.as_ref().0.len()) {
-        None => Err(
-            ocaml::Error::invalid_argument("caml_pasta_fq_plonk_index_create")
-                .err()
-                .unwrap(),
-        )?,
+// The constraint system of the gates, as the index is built from it, with the wires of the gates
+// as positions in the witness.
+fn constraint_system(
+    name: &'static str,
+    gates: &[Gate<Fq>],
+    public: usize,
+) -> Result<ConstraintSystem<Fq>, ocaml::Error> {
+    let n = match Domain::<Fq>::compute_size_of_domain(gates.len()) {
+        None => Err(ocaml::Error::invalid_argument(name).err().unwrap())?,
         Some(n) => n,
     };
     let wire = |w: Wire| -> usize {
@@ -136,8 +364,6 @@ pub fn caml_pasta_fq_plonk_index_create(
     };
 
     let gates: Vec<_> = gates
-        .as_ref()
-        .0
         .iter()
         .map(|gate| CircuitGate::<Fq> {
             typ: gate.typ.clone(),
@@ -150,17 +376,30 @@ pub fn caml_pasta_fq_plonk_index_create(
         })
         .collect();
 
-    let (endo_q, _endo_r) = commitment_dlog::srs::endos::<GAffineOther>();
-    let cs =
-        match ConstraintSystem::<Fq>::create(gates, oracle::pasta::fq::params(), public as usize)
-        {
-            None => Err(ocaml::Error::failwith(
-                "caml_pasta_fq_plonk_index_create: could not create constraint system",
-            )
+    match ConstraintSystem::<Fq>::create(gates, oracle::pasta::fq::params(), public) {
+        None => Err(ocaml::Error::failwith(format!("{}: could not create constraint system", name))
             .err()
-            .unwrap())?,
-            Some(cs) => cs,
-        };
+            .unwrap()),
+        Some(cs) => Ok(cs),
+    }
+}
+
+#[ocaml::func]
+pub fn caml_pasta_fq_plonk_index_create(
+    mut gates: CamlPastaFqPlonkGateVectorPtr,
+    public: ocaml::Int,
+    urs: CamlPastaFqUrs,
+) -> Result<CamlPastaFqPlonkIndex<'static>, ocaml::Error> {
+    {
+        let CamlPastaFqPlonkGateVector(gates, wiring) = gates.as_mut();
+        wiring.apply(gates)?;
+    }
+    let cs = constraint_system(
+        "caml_pasta_fq_plonk_index_create",
+        &gates.as_ref().0,
+        public as usize,
+    )?;
+    let (endo_q, _endo_r) = commitment_dlog::srs::endos::<GAffineOther>();
     let urs_copy = Rc::clone(&*urs);
     let urs_copy_outer = Rc::clone(&*urs);
     let srs = {
@@ -204,9 +443,67 @@ pub fn caml_pasta_fq_plonk_index_domain_d8_size(index: CamlPastaFqPlonkIndexPtr)
     index.as_ref().0.cs.domain.d8.size() as isize
 }
 
+#[ocaml::func]
+pub fn caml_pasta_fq_plonk_index_stats(index: CamlPastaFqPlonkIndexPtr) -> CamlPlonkCircuitStats {
+    let index = &index.as_ref().0;
+    let cs = &index.cs;
+    let mut succ: Vec<Option<usize>> = (0..3 * cs.domain.d1.size()).map(Some).collect();
+    for gate in cs.gates.iter() {
+        for (p, target) in [gate.wires.l, gate.wires.r, gate.wires.o].iter() {
+            if *p < succ.len() {
+                succ[*p] = Some(*target);
+            }
+        }
+    }
+    // The gates the index was built from, without the padding it added.
+    let canonical = plonk_circuit_digest::of_circuit_gates(&cs.gates, cs.domain.d1.size());
+    let gates = plonk_circuit_digest::without_padding(&canonical);
+    plonk_circuit_stats::stats(
+        plonk_circuit_stats::Shape {
+            rows: gates.len(),
+            public: cs.public,
+            d1: cs.domain.d1.size(),
+            d4: cs.domain.d4.size(),
+            d8: cs.domain.d8.size(),
+            urs_depth: Some(index.srs.get_ref().max_degree()),
+        },
+        plonk_circuit_stats::gate_counts(gates.iter().map(|gate| gate.typ)),
+        plonk_circuit_stats::cycles(succ.len(), |p| succ[p]),
+    )
+}
+
+/// A digest of the circuit of the index, to check that it was built for a given circuit.
+#[ocaml::func]
+pub fn caml_pasta_fq_plonk_index_digest(index: CamlPastaFqPlonkIndexPtr) -> ocaml::Value {
+    caml_bytes::of_slice(&index_digest(&index.as_ref().0))
+}
+
+/// Compares the circuits of two indices row by row, and their numbers of public inputs.
+#[ocaml::func]
+pub fn caml_pasta_fq_plonk_index_diff(
+    before: CamlPastaFqPlonkIndexPtr,
+    after: CamlPastaFqPlonkIndexPtr,
+) -> CamlPlonkCircuitDiff<Fq> {
+    let (before, after) = (&before.as_ref().0.cs, &after.as_ref().0.cs);
+    plonk_circuit_diff::diff(
+        Some((before.public, after.public)),
+        &plonk_circuit_digest::of_circuit_gates(&before.gates, before.domain.d1.size()),
+        &plonk_circuit_digest::of_circuit_gates(&after.gates, after.domain.d1.size()),
+    )
+}
+
+fn index_digest(index: &DlogIndex<GAffine>) -> Vec<u8> {
+    let cs = &index.cs;
+    let gates = plonk_circuit_digest::of_circuit_gates(&cs.gates, cs.domain.d1.size());
+    plonk_circuit_digest::digest(cs.public, cs.domain.d1.size(), &gates)
+}
+
+/// Reads an index written by `caml_pasta_fq_plonk_index_write`. When `digest` is given, fails
+/// unless the index was built for the circuit with that digest.
 #[ocaml::func]
 pub fn caml_pasta_fq_plonk_index_read(
     offset: Option<ocaml::Int>,
+    digest: Option<&[u8]>,
     urs: CamlPastaFqUrs,
     path: String,
 ) -> Result<CamlPastaFqPlonkIndex<'static>, ocaml::Error> {
@@ -238,6 +535,15 @@ pub fn caml_pasta_fq_plonk_index_read(
         srs,
         &mut r,
     )?;
+    if let Some(digest) = digest {
+        if index_digest(&t) != digest {
+            return Err(ocaml::Error::failwith(
+                "caml_pasta_fq_plonk_index_read: the index was built for a different circuit",
+            )
+            .err()
+            .unwrap());
+        }
+    }
     Ok(CamlPastaFqPlonkIndex(Box::new(t), urs_copy_outer))
 }
 
@@ -0,0 +1,212 @@
+use algebra::{
+    curves::{AffineCurve, ProjectiveCurve},
+    UniformRand, Zero,
+};
+use ff_fft::DensePolynomial;
+use mina_curves::pasta::{
+    fq::Fq,
+    pallas::{Affine as GAffine, PallasParameters},
+};
+
+use commitment_dlog::commitment::{CommitmentCurve, OpeningProof, PolyComm};
+use groupmap::GroupMap;
+use oracle::{poseidon::PlonkSpongeConstants, sponge::DefaultFqSponge, FqSponge};
+
+use crate::pasta_fq_urs::CamlPastaFqUrs;
+
+/* A general polynomial commitment interface over the URS. */
+
+type Sponge = DefaultFqSponge<PallasParameters, PlonkSpongeConstants>;
+
+fn degree_bound(
+    name: &'static str,
+    max: Option<ocaml::Int>,
+) -> Result<Option<usize>, ocaml::Error> {
+    match max {
+        Some(n) if n <= 0 => Err(ocaml::Error::invalid_argument(name).err().unwrap()),
+        max => Ok(max.map(|n| n as usize)),
+    }
+}
+
+/// Evaluates each chunk of `chunk_size` coefficients of `coeffs` at `x`.
+fn chunked_evaluations(coeffs: &[Fq], chunk_size: usize, x: Fq) -> Vec<Fq> {
+    if coeffs.is_empty() {
+        return vec![Fq::zero()];
+    }
+    coeffs
+        .chunks(std::cmp::max(chunk_size, 1))
+        .map(|chunk| chunk.iter().rev().fold(Fq::zero(), |acc, c| acc * &x + c))
+        .collect()
+}
+
+fn blind(h: GAffine, comm: &PolyComm<GAffine>, blinders: &PolyComm<Fq>) -> PolyComm<GAffine> {
+    let blind = |g: &GAffine, r: &Fq| (g.into_projective() + &h.mul(*r)).into_affine();
+    PolyComm {
+        unshifted: comm
+            .unshifted
+            .iter()
+            .zip(blinders.unshifted.iter())
+            .map(|(g, r)| blind(g, r))
+            .collect(),
+        shifted: comm
+            .shifted
+            .as_ref()
+            .zip(blinders.shifted.as_ref())
+            .map(|(g, r)| blind(g, r)),
+    }
+}
+
+// The transcript starts from the commitments and the evaluation points, so that the prover and
+// the verifier derive the same challenges.
+fn transcript(comms: &[&PolyComm<GAffine>], points: &[Fq]) -> Sponge {
+    let mut sponge = Sponge::new(oracle::pasta::fp::params());
+    for comm in comms {
+        sponge.absorb_g(&comm.unshifted);
+        if let Some(shifted) = comm.shifted {
+            sponge.absorb_g(&[shifted]);
+        }
+    }
+    sponge.absorb_fr(points);
+    sponge
+}
+
+/// Commits to the polynomial with coefficients `coeffs`, shifting the commitment for the
+/// degree bound `max_degree` if given.
+#[ocaml::func]
+pub fn caml_pasta_fq_poly_comm_commit(
+    urs: CamlPastaFqUrs,
+    coeffs: Vec<Fq>,
+    max_degree: Option<ocaml::Int>,
+) -> Result<PolyComm<GAffine>, ocaml::Error> {
+    let max = degree_bound("caml_pasta_fq_poly_comm_commit", max_degree)?;
+    let p = DensePolynomial::<Fq>::from_coefficients_vec(coeffs);
+    Ok((*urs).commit_non_hiding(&p, max))
+}
+
+/// As `caml_pasta_fq_poly_comm_commit`, but adds `r h` to each part of the commitment for a
+/// fresh random `r`. Returns the commitment with the blinding factors of its parts, which
+/// `caml_pasta_fq_poly_comm_open` takes to open it.
+#[ocaml::func]
+pub fn caml_pasta_fq_poly_comm_commit_hiding(
+    urs: CamlPastaFqUrs,
+    coeffs: Vec<Fq>,
+    max_degree: Option<ocaml::Int>,
+) -> Result<(PolyComm<GAffine>, PolyComm<Fq>), ocaml::Error> {
+    let max = degree_bound("caml_pasta_fq_poly_comm_commit_hiding", max_degree)?;
+    let p = DensePolynomial::<Fq>::from_coefficients_vec(coeffs);
+    let comm = (*urs).commit_non_hiding(&p, max);
+    let rng = &mut rand_core::OsRng;
+    let blinders = PolyComm {
+        unshifted: comm.unshifted.iter().map(|_| Fq::rand(rng)).collect(),
+        shifted: comm.shifted.map(|_| Fq::rand(rng)),
+    };
+    Ok((blind((*urs).h, &comm, &blinders), blinders))
+}
+
+/// Evaluates the polynomial at `x`, split into chunks of the size of the URS as it is committed
+/// to. These are the evaluations that `caml_pasta_fq_poly_comm_verify` expects.
+#[ocaml::func]
+pub fn caml_pasta_fq_poly_comm_evaluate(urs: CamlPastaFqUrs, coeffs: Vec<Fq>, x: Fq) -> Vec<Fq> {
+    chunked_evaluations(&coeffs, (*urs).g.len(), x)
+}
+
+/// Creates a batched opening proof of the polynomials, with their optional degree bounds, at
+/// all of the `points`. `polyscale` and `evalscale` combine the polynomials and the points.
+/// Each polynomial comes with the blinding factors returned by
+/// `caml_pasta_fq_poly_comm_commit_hiding` if it was committed to with it, or `None` if it was
+/// committed to with `caml_pasta_fq_poly_comm_commit`.
+#[ocaml::func]
+pub fn caml_pasta_fq_poly_comm_open(
+    urs: CamlPastaFqUrs,
+    polys: Vec<(Vec<Fq>, Option<ocaml::Int>, Option<PolyComm<Fq>>)>,
+    points: Vec<Fq>,
+    (polyscale, evalscale): (Fq, Fq),
+) -> Result<OpeningProof<GAffine>, ocaml::Error> {
+    let mut plnms = Vec::with_capacity(polys.len());
+    let mut comms = Vec::with_capacity(polys.len());
+    for (coeffs, max_degree, blinders) in polys.into_iter() {
+        let max = degree_bound("caml_pasta_fq_poly_comm_open", max_degree)?;
+        let p = DensePolynomial::<Fq>::from_coefficients_vec(coeffs);
+        let comm = (*urs).commit_non_hiding(&p, max);
+        let blinders = match blinders {
+            None => PolyComm {
+                unshifted: vec![Fq::zero(); comm.unshifted.len()],
+                shifted: comm.shifted.map(|_| Fq::zero()),
+            },
+            Some(b)
+                if b.unshifted.len() == comm.unshifted.len()
+                    && b.shifted.is_some() == comm.shifted.is_some() =>
+            {
+                b
+            }
+            // The blinding factors are for a commitment of another shape.
+            Some(_) => {
+                return Err(
+                    ocaml::Error::invalid_argument("caml_pasta_fq_poly_comm_open")
+                        .err()
+                        .unwrap(),
+                )
+            }
+        };
+        // The transcript absorbs the commitments as the verifier sees them.
+        comms.push(blind((*urs).h, &comm, &blinders));
+        plnms.push((p, max, blinders));
+    }
+    let sponge = transcript(&comms.iter().collect::<Vec<_>>(), &points);
+
+    let group_map = <GAffine as CommitmentCurve>::Map::setup();
+    Ok((*urs).open::<Sponge>(
+        &group_map,
+        plnms
+            .iter()
+            .map(|(p, max, blinders)| (p, *max, blinders.clone()))
+            .collect(),
+        &points,
+        polyscale,
+        evalscale,
+        sponge,
+        &mut rand_core::OsRng,
+    ))
+}
+
+/// Verifies an opening proof made by `caml_pasta_fq_poly_comm_open`. Each commitment comes with
+/// its degree bound and its chunked evaluations at each of the `points`.
+#[ocaml::func]
+pub fn caml_pasta_fq_poly_comm_verify(
+    urs: CamlPastaFqUrs,
+    comms: Vec<(PolyComm<GAffine>, Option<ocaml::Int>, Vec<Vec<Fq>>)>,
+    points: Vec<Fq>,
+    (polyscale, evalscale): (Fq, Fq),
+    proof: OpeningProof<GAffine>,
+) -> Result<bool, ocaml::Error> {
+    if comms
+        .iter()
+        .any(|(_, _, evals)| evals.len() != points.len())
+    {
+        return Err(
+            ocaml::Error::invalid_argument("caml_pasta_fq_poly_comm_verify")
+                .err()
+                .unwrap(),
+        );
+    }
+    let mut bounds = Vec::with_capacity(comms.len());
+    for (_, max_degree, _) in comms.iter() {
+        bounds.push(degree_bound("caml_pasta_fq_poly_comm_verify", *max_degree)?);
+    }
+    let sponge = transcript(
+        &comms.iter().map(|(comm, _, _)| comm).collect::<Vec<_>>(),
+        &points,
+    );
+
+    let group_map = <GAffine as CommitmentCurve>::Map::setup();
+    let polys = comms
+        .iter()
+        .zip(bounds.into_iter())
+        .map(|((comm, _, evals), max)| (comm, evals.iter().collect(), max))
+        .collect();
+    Ok((*urs).verify::<Sponge>(
+        &group_map,
+        &mut vec![(sponge, points.clone(), polyscale, evalscale, polys, &proof)],
+        &mut rand_core::OsRng,
+    ))
+}
@@ -1,13 +1,113 @@
 use mina_curves::pasta::{
     pallas::{Affine as GAffine, Projective as GProjective},
-    fp::Fp,
+    fp::{Fp, FpParameters as Fp_params},
     fq::Fq,
 };
 use algebra::{
+    biginteger::{BigInteger, BigInteger256},
     curves::{AffineCurve, ProjectiveCurve},
-    One, UniformRand,
+    fields::{Field, FpParameters, PrimeField},
+    One, UniformRand, VariableBaseMSM, Zero,
 };
 use rand::rngs::StdRng;
+use rayon::prelude::*;
+use std::convert::TryInto;
+
+use crate::caml_bytes;
+
+/* Point encodings. Coordinates are written as canonical little-endian integers. The compressed
+ * encoding stores the parity of y in the top bit of x, which is always free since the modulus
+ * has 255 bits. The point at infinity is encoded as all ones, which is never a canonical
+ * coordinate. */
+
+const COORDINATE_BYTES: usize = 32;
+const COMPRESSED_BYTES: usize = COORDINATE_BYTES;
+const UNCOMPRESSED_BYTES: usize = 2 * COORDINATE_BYTES;
+const SIGN_BIT: u8 = 0x80;
+
+fn coordinate_to_bytes(x: &Fp, out: &mut [u8]) {
+    let repr = x.into_repr();
+    for (i, limb) in repr.0.iter().enumerate() {
+        out[8 * i..8 * (i + 1)].copy_from_slice(&limb.to_le_bytes());
+    }
+}
+
+fn coordinate_of_bytes(bytes: &[u8]) -> Option<Fp> {
+    let mut repr = BigInteger256::default();
+    for i in 0..repr.0.len() {
+        repr.0[i] = u64::from_le_bytes(bytes[8 * i..8 * (i + 1)].try_into().unwrap());
+    }
+    if repr < Fp_params::MODULUS {
+        Some(Fp::from_repr(repr))
+    } else {
+        None
+    }
+}
+
+fn is_infinity_encoding(bytes: &[u8]) -> bool {
+    bytes.iter().all(|b| *b == 0xff)
+}
+
+pub fn affine_to_compressed(p: &GAffine) -> [u8; COMPRESSED_BYTES] {
+    let mut res = [0xff; COMPRESSED_BYTES];
+    if !p.infinity {
+        coordinate_to_bytes(&p.x, &mut res);
+        if p.y.into_repr().is_odd() {
+            res[COMPRESSED_BYTES - 1] |= SIGN_BIT;
+        }
+    }
+    res
+}
+
+pub fn affine_of_compressed(bytes: &[u8]) -> Option<GAffine> {
+    if bytes.len() != COMPRESSED_BYTES {
+        return None;
+    }
+    if is_infinity_encoding(bytes) {
+        return Some(GProjective::zero().into_affine());
+    }
+    let mut x_bytes = [0u8; COORDINATE_BYTES];
+    x_bytes.copy_from_slice(bytes);
+    let odd = x_bytes[COORDINATE_BYTES - 1] & SIGN_BIT != 0;
+    x_bytes[COORDINATE_BYTES - 1] &= !SIGN_BIT;
+    let x = coordinate_of_bytes(&x_bytes)?;
+    let p = GAffine::get_point_from_x(x, false)?;
+    if p.y.is_zero() && odd {
+        // Zero has no odd negation, so this encoding is not canonical.
+        return None;
+    }
+    if p.y.into_repr().is_odd() == odd {
+        Some(p)
+    } else {
+        Some(-p)
+    }
+}
+
+pub fn affine_to_uncompressed(p: &GAffine) -> [u8; UNCOMPRESSED_BYTES] {
+    let mut res = [0xff; UNCOMPRESSED_BYTES];
+    if !p.infinity {
+        coordinate_to_bytes(&p.x, &mut res[..COORDINATE_BYTES]);
+        coordinate_to_bytes(&p.y, &mut res[COORDINATE_BYTES..]);
+    }
+    res
+}
+
+pub fn affine_of_uncompressed(bytes: &[u8]) -> Option<GAffine> {
+    if bytes.len() != UNCOMPRESSED_BYTES {
+        return None;
+    }
+    if is_infinity_encoding(bytes) {
+        return Some(GProjective::zero().into_affine());
+    }
+    let x = coordinate_of_bytes(&bytes[..COORDINATE_BYTES])?;
+    let y = coordinate_of_bytes(&bytes[COORDINATE_BYTES..])?;
+    let p = GAffine::new(x, y, false);
+    if p.is_on_curve() {
+        Some(p)
+    } else {
+        None
+    }
+}
 
 #[ocaml::func]
 pub fn caml_pasta_pallas_one() -> GProjective {
@@ -45,6 +145,43 @@ pub fn caml_pasta_pallas_scale(x: ocaml::Pointer<GProjective>, y: Fq) -> GProjec
     x.as_ref().mul(y)
 }
 
+#[ocaml::func]
+pub fn caml_pasta_pallas_msm(
+    points: Vec<GAffine>,
+    scalars: Vec<Fq>,
+) -> Result<GProjective, ocaml::Error> {
+    if points.len() != scalars.len() {
+        return Err(ocaml::Error::invalid_argument("caml_pasta_pallas_msm")
+            .err()
+            .unwrap());
+    }
+    let scalars: Vec<_> = scalars.iter().map(|x| x.into_repr()).collect();
+    Ok(VariableBaseMSM::multi_scalar_mul(&points, &scalars))
+}
+
+/// Splits the inputs into chunks of `chunk_size` terms, computes the MSM of each chunk in
+/// parallel and sums the results.
+#[ocaml::func]
+pub fn caml_pasta_pallas_msm_chunked(
+    points: Vec<GAffine>,
+    scalars: Vec<Fq>,
+    chunk_size: ocaml::Int,
+) -> Result<GProjective, ocaml::Error> {
+    if points.len() != scalars.len() || chunk_size <= 0 {
+        return Err(
+            ocaml::Error::invalid_argument("caml_pasta_pallas_msm_chunked")
+                .err()
+                .unwrap(),
+        );
+    }
+    let scalars: Vec<_> = scalars.par_iter().map(|x| x.into_repr()).collect();
+    Ok(points
+        .par_chunks(chunk_size as usize)
+        .zip(scalars.par_chunks(chunk_size as usize))
+        .map(|(points, scalars)| VariableBaseMSM::multi_scalar_mul(points, scalars))
+        .reduce(GProjective::zero, |acc, x| acc + &x))
+}
+
 #[ocaml::func]
 pub fn caml_pasta_pallas_random() -> GProjective {
     let rng = &mut rand_core::OsRng;
@@ -71,6 +208,91 @@ pub extern "C" fn caml_pasta_pallas_endo_scalar() -> Fq {
     endo_r
 }
 
+/* Scalar challenges. A challenge of `n` bits `r` is interpreted as the scalar
+ * `a * endo_r + b`, following the algorithm on page 29 of the Halo paper
+ * (https://eprint.iacr.org/2019/1021.pdf). `endo_scale` computes the same multiple of a point
+ * using the base field endomorphism, in the same sequence of steps as the `Endomul1`-`Endomul4`
+ * gates. */
+
+const MAX_CHALLENGE_BITS: ocaml::Int = 256;
+
+fn challenge_bits(
+    name: &'static str,
+    length_in_bits: ocaml::Int,
+    chal: &BigInteger256,
+) -> Result<Vec<bool>, ocaml::Error> {
+    if length_in_bits <= 0
+        || length_in_bits > MAX_CHALLENGE_BITS
+        || chal.num_bits() as ocaml::Int > length_in_bits
+    {
+        return Err(ocaml::Error::invalid_argument(name).err().unwrap());
+    }
+    let n = length_in_bits as usize;
+    // Odd lengths are padded with a zero bit, as in the circuit.
+    Ok((0..n + n % 2).map(|i| i < n && chal.get_bit(i)).collect())
+}
+
+pub fn scalar_challenge_to_field(endo: Fq, bits: &[bool]) -> Fq {
+    let mut a = Fq::from(2u64);
+    let mut b = Fq::from(2u64);
+    for i in (0..bits.len() / 2).rev() {
+        let s = if bits[2 * i] { Fq::one() } else { -Fq::one() };
+        a.double_in_place();
+        b.double_in_place();
+        if bits[2 * i + 1] {
+            a += &s
+        } else {
+            b += &s
+        }
+    }
+    a * &endo + &b
+}
+
+pub fn endo_scale(p: &GAffine, bits: &[bool]) -> GProjective {
+    if p.infinity {
+        return GProjective::zero();
+    }
+    let (endo_q, _endo_r) = commitment_dlog::srs::endos::<GAffine>();
+    let endo_p = GAffine::new(endo_q * &p.x, p.y, false);
+    // acc = 2 (endo(P) + P)
+    let mut acc = endo_p.into_projective();
+    acc.add_assign_mixed(p);
+    acc.double_in_place();
+    for i in (0..bits.len() / 2).rev() {
+        // S = +/- P or +/- endo(P), then acc = (acc + S) + acc
+        let x = if bits[2 * i + 1] { endo_p.x } else { p.x };
+        let y = if bits[2 * i] { p.y } else { -p.y };
+        let mut s = acc;
+        s.add_assign_mixed(&GAffine::new(x, y, false));
+        acc += &s;
+    }
+    acc
+}
+
+#[ocaml::func]
+pub fn caml_pasta_pallas_scalar_challenge_to_field(
+    endo: Fq,
+    length_in_bits: ocaml::Int,
+    chal: ocaml::Pointer<BigInteger256>,
+) -> Result<Fq, ocaml::Error> {
+    let bits = challenge_bits(
+        "caml_pasta_pallas_scalar_challenge_to_field",
+        length_in_bits,
+        chal.as_ref(),
+    )?;
+    Ok(scalar_challenge_to_field(endo, &bits))
+}
+
+#[ocaml::func]
+pub fn caml_pasta_pallas_endo_scale(
+    p: ocaml::Pointer<GProjective>,
+    chal: ocaml::Pointer<BigInteger256>,
+    length_in_bits: ocaml::Int,
+) -> Result<GProjective, ocaml::Error> {
+    let bits = challenge_bits("caml_pasta_pallas_endo_scale", length_in_bits, chal.as_ref())?;
+    Ok(endo_scale(&p.as_ref().into_affine(), &bits))
+}
+
 #[ocaml::func]
 pub fn caml_pasta_pallas_to_affine(x: ocaml::Pointer<GProjective>) -> GAffine {
     x.as_ref().into_affine().into()
@@ -81,11 +303,83 @@ pub fn caml_pasta_pallas_of_affine(x: GAffine) -> GProjective {
     Into::<GAffine>::into(x).into_projective()
 }
 
+/// Normalizes all of the points with a single shared field inversion.
+#[ocaml::func]
+pub fn caml_pasta_pallas_batch_to_affine(xs: Vec<ocaml::Pointer<GProjective>>) -> Vec<GAffine> {
+    let xs: Vec<GProjective> = xs.iter().map(|x| *x.as_ref()).collect();
+    GProjective::batch_normalization_into_affine(&xs)
+}
+
+#[ocaml::func]
+pub fn caml_pasta_pallas_batch_of_affine(xs: Vec<GAffine>) -> Vec<GProjective> {
+    xs.par_iter().map(|x| x.into_projective()).collect()
+}
+
 #[ocaml::func]
 pub fn caml_pasta_pallas_of_affine_coordinates(x: Fp, y: Fp) -> GProjective {
     GProjective::new(x, y, Fp::one())
 }
 
+#[ocaml::func]
+pub fn caml_pasta_pallas_of_affine_coordinates_checked(
+    x: Fp,
+    y: Fp,
+) -> Result<GProjective, ocaml::Error> {
+    let p = GAffine::new(x, y, false);
+    if !p.is_on_curve() {
+        return Err(ocaml::Error::failwith(
+            "caml_pasta_pallas_of_affine_coordinates_checked: point is not on the curve",
+        )
+        .err()
+        .unwrap());
+    }
+    Ok(p.into_projective())
+}
+
+#[ocaml::func]
+pub fn caml_pasta_pallas_is_on_curve(x: ocaml::Pointer<GProjective>) -> bool {
+    x.as_ref().into_affine().is_on_curve()
+}
+
+#[ocaml::func]
+pub fn caml_pasta_pallas_affine_is_on_curve(x: GAffine) -> bool {
+    x.infinity || x.is_on_curve()
+}
+
+#[ocaml::func]
+pub fn caml_pasta_pallas_affine_to_compressed_bytes(x: GAffine) -> ocaml::Value {
+    caml_bytes::of_slice(&affine_to_compressed(&x))
+}
+
+#[ocaml::func]
+pub fn caml_pasta_pallas_affine_of_compressed_bytes(x: &[u8]) -> Result<GAffine, ocaml::Error> {
+    match affine_of_compressed(x) {
+        Some(p) => Ok(p),
+        None => Err(ocaml::Error::failwith(
+            "caml_pasta_pallas_affine_of_compressed_bytes: invalid point encoding",
+        )
+        .err()
+        .unwrap()),
+    }
+}
+
+#[ocaml::func]
+pub fn caml_pasta_pallas_affine_to_uncompressed_bytes(x: GAffine) -> ocaml::Value {
+    caml_bytes::of_slice(&affine_to_uncompressed(&x))
+}
+
+#[ocaml::func]
+pub fn caml_pasta_pallas_affine_of_uncompressed_bytes(x: &[u8]) -> Result<GAffine, ocaml::Error> {
+    match affine_of_uncompressed(x) {
+        Some(p) => Ok(p),
+        None => Err(ocaml::Error::failwith(
+            "caml_pasta_pallas_affine_of_uncompressed_bytes: invalid point encoding",
+        )
+        .err()
+        .unwrap()),
+    }
+}
+
 #[ocaml::func]
 pub fn caml_pasta_pallas_affine_deep_copy(x: GAffine) -> GAffine {
     x
@@ -0,0 +1,80 @@
+use algebra::{fields::FpParameters, FixedBaseMSM};
+use mina_curves::pasta::{
+    fq::{Fq, FqParameters as Fq_params},
+    pallas::{Affine as GAffine, Projective as GProjective},
+};
+use rayon::prelude::*;
+
+/* A precomputed table of the multiples of a fixed base, for repeated scalar multiplications
+ * by the same point. Row `i` holds `j * 2^(i * window) * base` for every `j < 2^window`. */
+
+pub struct CamlPastaPallasFixedBase {
+    pub window: usize,
+    pub table: Vec<Vec<GAffine>>,
+}
+pub type CamlPastaPallasFixedBasePtr<'a> = ocaml::Pointer<'a, CamlPastaPallasFixedBase>;
+
+extern "C" fn caml_pasta_pallas_fixed_base_finalize(v: ocaml::Raw) {
+    unsafe {
+        let v: CamlPastaPallasFixedBasePtr = v.as_pointer();
+        v.drop_in_place()
+    };
+}
+
+ocaml::custom!(CamlPastaPallasFixedBase {
+    finalize: caml_pasta_pallas_fixed_base_finalize,
+});
+
+// Larger windows make the table grow as `2^window`, for diminishing returns.
+const MAX_WINDOW: ocaml::Int = 16;
+
+const SCALAR_BITS: usize = Fq_params::MODULUS_BITS as usize;
+
+impl CamlPastaPallasFixedBase {
+    pub fn create(base: GProjective, window: usize) -> Self {
+        CamlPastaPallasFixedBase {
+            window,
+            table: FixedBaseMSM::get_window_table(SCALAR_BITS, window, base),
+        }
+    }
+
+    pub fn scale(&self, x: &Fq) -> GProjective {
+        let outerc = (SCALAR_BITS + self.window - 1) / self.window;
+        FixedBaseMSM::windowed_mul(outerc, self.window, &self.table, x)
+    }
+}
+
+#[ocaml::func]
+pub fn caml_pasta_pallas_fixed_base_create(
+    base: ocaml::Pointer<GProjective>,
+    window: ocaml::Int,
+) -> Result<CamlPastaPallasFixedBase, ocaml::Error> {
+    if window <= 0 || window > MAX_WINDOW {
+        return Err(
+            ocaml::Error::invalid_argument("caml_pasta_pallas_fixed_base_create")
+                .err()
+                .unwrap(),
+        );
+    }
+    Ok(CamlPastaPallasFixedBase::create(*base.as_ref(), window as usize))
+}
+
+#[ocaml::func]
+pub fn caml_pasta_pallas_fixed_base_base(t: CamlPastaPallasFixedBasePtr) -> GAffine {
+    // The first multiple in the second column is `1 * 2^0 * base`.
+    t.as_ref().table[0][1]
+}
+
+#[ocaml::func]
+pub fn caml_pasta_pallas_fixed_base_scale(t: CamlPastaPallasFixedBasePtr, x: Fq) -> GProjective {
+    t.as_ref().scale(&x)
+}
+
+#[ocaml::func]
+pub fn caml_pasta_pallas_fixed_base_batch_scale(
+    t: CamlPastaPallasFixedBasePtr,
+    xs: Vec<Fq>,
+) -> Vec<GProjective> {
+    let t = t.as_ref();
+    xs.par_iter().map(|x| t.scale(x)).collect()
+}
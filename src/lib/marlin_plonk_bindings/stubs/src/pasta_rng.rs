@@ -0,0 +1,131 @@
+use algebra::UniformRand;
+use blake2::{Blake2s, Digest};
+use mina_curves::pasta::{
+    fp::Fp, fq::Fq, pallas::Projective as GProjectivePallas, vesta::Projective as GProjectiveVesta,
+};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::caml_bytes;
+
+/* A deterministic RNG, seeded from arbitrary bytes, so that OCaml tests can replay long
+ * sequences of field elements and points.
+ *
+ * The stream is ChaCha20 keyed with the Blake2s hash of the seed. Unlike `StdRng`, whose
+ * algorithm may change between versions of `rand`, it is the same for a seed everywhere. */
+
+pub struct CamlPastaRng(ChaCha20Rng);
+pub type CamlPastaRngPtr<'a> = ocaml::Pointer<'a, CamlPastaRng>;
+
+extern "C" fn caml_pasta_rng_finalize(v: ocaml::Raw) {
+    unsafe {
+        let v: CamlPastaRngPtr = v.as_pointer();
+        v.drop_in_place()
+    };
+}
+
+ocaml::custom!(CamlPastaRng {
+    finalize: caml_pasta_rng_finalize,
+});
+
+impl CamlPastaRng {
+    pub fn of_seed(seed: &[u8]) -> Self {
+        // Hash the seed so that every byte of it contributes to the state, whatever its length.
+        let mut s = <ChaCha20Rng as SeedableRng>::Seed::default();
+        s.copy_from_slice(&Blake2s::digest(seed));
+        CamlPastaRng(ChaCha20Rng::from_seed(s))
+    }
+
+    pub fn rng(&mut self) -> &mut ChaCha20Rng {
+        &mut self.0
+    }
+}
+
+fn sample_vec<T: UniformRand>(
+    rng: &mut ChaCha20Rng,
+    len: ocaml::Int,
+) -> Result<Vec<T>, ocaml::Error> {
+    if len < 0 {
+        return Err(
+            ocaml::Error::invalid_argument("caml_pasta_rng: negative length")
+                .err()
+                .unwrap(),
+        );
+    }
+    Ok((0..len).map(|_| UniformRand::rand(rng)).collect())
+}
+
+#[ocaml::func]
+pub fn caml_pasta_rng_create(seed: &[u8]) -> CamlPastaRng {
+    CamlPastaRng::of_seed(seed)
+}
+
+/// The next `len` bytes of the stream.
+#[ocaml::func]
+pub fn caml_pasta_rng_bytes(
+    mut rng: CamlPastaRngPtr,
+    len: ocaml::Int,
+) -> Result<ocaml::Value, ocaml::Error> {
+    if len < 0 {
+        return Err(
+            ocaml::Error::invalid_argument("caml_pasta_rng: negative length")
+                .err()
+                .unwrap(),
+        );
+    }
+    let mut bytes = vec![0u8; len as usize];
+    rng.as_mut().rng().fill_bytes(&mut bytes);
+    Ok(caml_bytes::of_slice(&bytes))
+}
+
+#[ocaml::func]
+pub fn caml_pasta_rng_fp(mut rng: CamlPastaRngPtr) -> Fp {
+    UniformRand::rand(rng.as_mut().rng())
+}
+
+#[ocaml::func]
+pub fn caml_pasta_rng_fq(mut rng: CamlPastaRngPtr) -> Fq {
+    UniformRand::rand(rng.as_mut().rng())
+}
+
+#[ocaml::func]
+pub fn caml_pasta_rng_pallas(mut rng: CamlPastaRngPtr) -> GProjectivePallas {
+    UniformRand::rand(rng.as_mut().rng())
+}
+
+#[ocaml::func]
+pub fn caml_pasta_rng_vesta(mut rng: CamlPastaRngPtr) -> GProjectiveVesta {
+    UniformRand::rand(rng.as_mut().rng())
+}
+
+#[ocaml::func]
+pub fn caml_pasta_rng_fp_array(
+    mut rng: CamlPastaRngPtr,
+    len: ocaml::Int,
+) -> Result<Vec<Fp>, ocaml::Error> {
+    sample_vec(rng.as_mut().rng(), len)
+}
+
+#[ocaml::func]
+pub fn caml_pasta_rng_fq_array(
+    mut rng: CamlPastaRngPtr,
+    len: ocaml::Int,
+) -> Result<Vec<Fq>, ocaml::Error> {
+    sample_vec(rng.as_mut().rng(), len)
+}
+
+#[ocaml::func]
+pub fn caml_pasta_rng_pallas_array(
+    mut rng: CamlPastaRngPtr,
+    len: ocaml::Int,
+) -> Result<Vec<GProjectivePallas>, ocaml::Error> {
+    sample_vec(rng.as_mut().rng(), len)
+}
+
+#[ocaml::func]
+pub fn caml_pasta_rng_vesta_array(
+    mut rng: CamlPastaRngPtr,
+    len: ocaml::Int,
+) -> Result<Vec<GProjectiveVesta>, ocaml::Error> {
+    sample_vec(rng.as_mut().rng(), len)
+}
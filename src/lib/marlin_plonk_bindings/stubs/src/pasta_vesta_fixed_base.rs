@@ -0,0 +1,80 @@
+use algebra::{fields::FpParameters, FixedBaseMSM};
+use mina_curves::pasta::{
+    fp::{Fp, FpParameters as Fp_params},
+    vesta::{Affine as GAffine, Projective as GProjective},
+};
+use rayon::prelude::*;
+
+/* A precomputed table of the multiples of a fixed base, for repeated scalar multiplications
+ * by the same point. Row `i` holds `j * 2^(i * window) * base` for every `j < 2^window`. */
+
+pub struct CamlPastaVestaFixedBase {
+    pub window: usize,
+    pub table: Vec<Vec<GAffine>>,
+}
+pub type CamlPastaVestaFixedBasePtr<'a> = ocaml::Pointer<'a, CamlPastaVestaFixedBase>;
+
+extern "C" fn caml_pasta_vesta_fixed_base_finalize(v: ocaml::Raw) {
+    unsafe {
+        let v: CamlPastaVestaFixedBasePtr = v.as_pointer();
+        v.drop_in_place()
+    };
+}
+
+ocaml::custom!(CamlPastaVestaFixedBase {
+    finalize: caml_pasta_vesta_fixed_base_finalize,
+});
+
+// Larger windows make the table grow as `2^window`, for diminishing returns.
+const MAX_WINDOW: ocaml::Int = 16;
+
+const SCALAR_BITS: usize = Fp_params::MODULUS_BITS as usize;
+
+impl CamlPastaVestaFixedBase {
+    pub fn create(base: GProjective, window: usize) -> Self {
+        CamlPastaVestaFixedBase {
+            window,
+            table: FixedBaseMSM::get_window_table(SCALAR_BITS, window, base),
+        }
+    }
+
+    pub fn scale(&self, x: &Fp) -> GProjective {
+        let outerc = (SCALAR_BITS + self.window - 1) / self.window;
+        FixedBaseMSM::windowed_mul(outerc, self.window, &self.table, x)
+    }
+}
+
+#[ocaml::func]
+pub fn caml_pasta_vesta_fixed_base_create(
+    base: ocaml::Pointer<GProjective>,
+    window: ocaml::Int,
+) -> Result<CamlPastaVestaFixedBase, ocaml::Error> {
+    if window <= 0 || window > MAX_WINDOW {
+        return Err(
+            ocaml::Error::invalid_argument("caml_pasta_vesta_fixed_base_create")
+                .err()
+                .unwrap(),
+        );
+    }
+    Ok(CamlPastaVestaFixedBase::create(*base.as_ref(), window as usize))
+}
+
+#[ocaml::func]
+pub fn caml_pasta_vesta_fixed_base_base(t: CamlPastaVestaFixedBasePtr) -> GAffine {
+    // The first multiple in the second column is `1 * 2^0 * base`.
+    t.as_ref().table[0][1]
+}
+
+#[ocaml::func]
+pub fn caml_pasta_vesta_fixed_base_scale(t: CamlPastaVestaFixedBasePtr, x: Fp) -> GProjective {
+    t.as_ref().scale(&x)
+}
+
+#[ocaml::func]
+pub fn caml_pasta_vesta_fixed_base_batch_scale(
+    t: CamlPastaVestaFixedBasePtr,
+    xs: Vec<Fp>,
+) -> Vec<GProjective> {
+    let t = t.as_ref();
+    xs.par_iter().map(|x| t.scale(x)).collect()
+}
@@ -0,0 +1,83 @@
+use algebra::{biginteger::BigInteger256, fields::PrimeField};
+use commitment_dlog::commitment::CommitmentCurve;
+use groupmap::GroupMap;
+use mina_curves::pasta::{fq::Fq, vesta::Affine as GAffine};
+use oracle::poseidon::{ArithmeticSponge, PlonkSpongeConstants, Sponge};
+use rayon::prelude::*;
+use std::convert::TryInto;
+
+/* The group map used to send field elements onto the Vesta curve. This is the same map that
+ * proof creation and verification set up with `GroupMap::setup()`. */
+
+pub type VestaGroupMap = <GAffine as CommitmentCurve>::Map;
+
+pub struct CamlPastaVestaGroupMap(pub VestaGroupMap);
+pub type CamlPastaVestaGroupMapPtr<'a> = ocaml::Pointer<'a, CamlPastaVestaGroupMap>;
+
+extern "C" fn caml_pasta_vesta_group_map_finalize(v: ocaml::Raw) {
+    unsafe {
+        let v: CamlPastaVestaGroupMapPtr = v.as_pointer();
+        v.drop_in_place()
+    };
+}
+
+ocaml::custom!(CamlPastaVestaGroupMap {
+    finalize: caml_pasta_vesta_group_map_finalize,
+});
+
+// Every 31-byte chunk fits below the modulus, so the packing is injective for a given length.
+const BYTES_PER_FIELD_ELEMENT: usize = 31;
+
+fn field_of_chunk(chunk: &[u8]) -> Fq {
+    let mut bytes = [0u8; 32];
+    bytes[..chunk.len()].copy_from_slice(chunk);
+    let mut repr = BigInteger256::default();
+    for i in 0..repr.0.len() {
+        repr.0[i] = u64::from_le_bytes(bytes[8 * i..8 * (i + 1)].try_into().unwrap());
+    }
+    Fq::from_repr(repr)
+}
+
+pub fn to_group(map: &VestaGroupMap, u: Fq) -> GAffine {
+    let (x, y) = map.to_group(u);
+    GAffine::new(x, y, false)
+}
+
+/// Absorbs the length of `bytes` followed by `bytes` packed into field elements, squeezes a
+/// single field element and maps it onto the curve.
+pub fn hash_to_curve(map: &VestaGroupMap, bytes: &[u8]) -> GAffine {
+    let params = oracle::pasta::fq::params();
+    let mut sponge = ArithmeticSponge::<Fq, PlonkSpongeConstants>::new();
+    sponge.absorb(&params, &[Fq::from(bytes.len() as u64)]);
+    for chunk in bytes.chunks(BYTES_PER_FIELD_ELEMENT) {
+        sponge.absorb(&params, &[field_of_chunk(chunk)]);
+    }
+    to_group(map, sponge.squeeze(&params))
+}
+
+#[ocaml::func]
+pub fn caml_pasta_vesta_group_map_create() -> CamlPastaVestaGroupMap {
+    CamlPastaVestaGroupMap(VestaGroupMap::setup())
+}
+
+#[ocaml::func]
+pub fn caml_pasta_vesta_group_map_to_group(map: CamlPastaVestaGroupMapPtr, u: Fq) -> GAffine {
+    to_group(&map.as_ref().0, u)
+}
+
+#[ocaml::func]
+pub fn caml_pasta_vesta_group_map_batch_to_group(
+    map: CamlPastaVestaGroupMapPtr,
+    us: Vec<Fq>,
+) -> Vec<GAffine> {
+    let map = &map.as_ref().0;
+    us.into_par_iter().map(|u| to_group(map, u)).collect()
+}
+
+#[ocaml::func]
+pub fn caml_pasta_vesta_group_map_hash_to_curve(
+    map: CamlPastaVestaGroupMapPtr,
+    bytes: &[u8],
+) -> GAffine {
+    hash_to_curve(&map.as_ref().0, bytes)
+}
@@ -0,0 +1,169 @@
+use algebra::fields::PrimeField;
+use plonk_circuits::wires::{Col::*, Wire};
+
+use crate::plonk_circuit_digest::{self, CanonicalGate};
+use crate::plonk_circuit_stats::kind_index;
+use crate::plonk_gate::{CamlPlonkCol, CamlPlonkGate, CamlPlonkWires};
+use crate::plonk_verifier_index::CamlPlonkVerifierIndex;
+
+/* Comparing two circuits row by row, to find which gates a change to a circuit affected.
+ *
+ * Gates are compared as they are digested by `plonk_circuit_digest`, so a gate vector can be
+ * compared with the index built from it, and the padding that the index adds is not reported.
+ * Two verifier indices are compared field by field. */
+
+#[derive(ocaml::IntoValue, ocaml::FromValue)]
+pub enum CamlPlonkGateChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(ocaml::IntoValue, ocaml::FromValue)]
+pub struct CamlPlonkGateDiff<F> {
+    pub row: ocaml::Int,
+    pub change: CamlPlonkGateChange,
+    pub before: Option<CamlPlonkGate<Vec<F>>>,
+    pub after: Option<CamlPlonkGate<Vec<F>>>,
+    pub coefficients: Vec<ocaml::Int>, // the indices of the coefficients that differ
+    pub rewired: Vec<CamlPlonkCol>,    // the wires whose copy constraint differs
+}
+
+#[derive(ocaml::IntoValue, ocaml::FromValue)]
+pub struct CamlPlonkCircuitDiff<F> {
+    pub rows: (ocaml::Int, ocaml::Int),
+    pub public_inputs: Option<(ocaml::Int, ocaml::Int)>, // when the number differs
+    pub gates: Vec<CamlPlonkGateDiff<F>>,                // the rows that differ, in order
+}
+
+fn same_wire(a: &Wire, b: &Wire) -> bool {
+    a.row == b.row
+        && match (&a.col, &b.col) {
+            (L, L) | (R, R) | (O, O) => true,
+            _ => false,
+        }
+}
+
+fn to_caml<F: PrimeField>(gate: &CanonicalGate<F>) -> CamlPlonkGate<Vec<F>> {
+    CamlPlonkGate {
+        typ: gate.typ.into(),
+        wires: CamlPlonkWires {
+            row: gate.row as ocaml::Int,
+            l: (&gate.wires[0]).into(),
+            r: (&gate.wires[1]).into(),
+            o: (&gate.wires[2]).into(),
+        },
+        c: gate.c.to_vec(),
+    }
+}
+
+fn gate_diff<F: PrimeField>(
+    row: usize,
+    before: Option<&CanonicalGate<F>>,
+    after: Option<&CanonicalGate<F>>,
+) -> Option<CamlPlonkGateDiff<F>> {
+    let (change, coefficients, rewired) = match (before, after) {
+        (None, None) => return None,
+        (None, Some(_)) => (CamlPlonkGateChange::Added, vec![], vec![]),
+        (Some(_), None) => (CamlPlonkGateChange::Removed, vec![], vec![]),
+        (Some(a), Some(b)) => {
+            let len = std::cmp::max(a.c.len(), b.c.len());
+            let coefficients: Vec<ocaml::Int> = (0..len)
+                .filter(|i| a.c.get(*i) != b.c.get(*i))
+                .map(|i| i as ocaml::Int)
+                .collect();
+            let rewired: Vec<CamlPlonkCol> = [L, R, O]
+                .iter()
+                .zip(a.wires.iter().zip(b.wires.iter()))
+                .filter(|(_, (x, y))| !same_wire(x, y))
+                .map(|(col, _)| col.into())
+                .collect();
+            if kind_index(a.typ) == kind_index(b.typ)
+                && coefficients.is_empty()
+                && rewired.is_empty()
+            {
+                return None;
+            }
+            (CamlPlonkGateChange::Modified, coefficients, rewired)
+        }
+    };
+    Some(CamlPlonkGateDiff {
+        row: row as ocaml::Int,
+        change,
+        before: before.map(to_caml),
+        after: after.map(to_caml),
+        coefficients,
+        rewired,
+    })
+}
+
+/// Compares the gates of two circuits row by row. The numbers of public inputs are compared when
+/// both are known.
+pub fn diff<F: PrimeField>(
+    public: Option<(usize, usize)>,
+    before: &[CanonicalGate<F>],
+    after: &[CanonicalGate<F>],
+) -> CamlPlonkCircuitDiff<F> {
+    let before = plonk_circuit_digest::without_padding(before);
+    let after = plonk_circuit_digest::without_padding(after);
+    let rows = std::cmp::max(before.len(), after.len());
+    CamlPlonkCircuitDiff {
+        rows: (before.len() as ocaml::Int, after.len() as ocaml::Int),
+        public_inputs: public
+            .filter(|(a, b)| a != b)
+            .map(|(a, b)| (a as ocaml::Int, b as ocaml::Int)),
+        gates: (0..rows)
+            .filter_map(|row| gate_diff(row, before.get(row), after.get(row)))
+            .collect(),
+    }
+}
+
+/// The names of the fields of two verifier indices that differ, as they are named in OCaml. The
+/// URSs are not compared.
+pub fn verifier_index_diff<Fr, URS, PolyComm, Eq>(
+    a: &CamlPlonkVerifierIndex<Fr, URS, PolyComm>,
+    b: &CamlPlonkVerifierIndex<Fr, URS, PolyComm>,
+    same_comm: Eq,
+) -> Vec<String>
+where
+    Fr: PartialEq,
+    Eq: Fn(&PolyComm, &PolyComm) -> bool,
+{
+    let (x, y) = (&a.evals, &b.evals);
+    let fields = [
+        (
+            "domain",
+            a.domain.log_size_of_group == b.domain.log_size_of_group
+                && a.domain.group_gen == b.domain.group_gen,
+        ),
+        ("max_poly_size", a.max_poly_size == b.max_poly_size),
+        ("max_quot_size", a.max_quot_size == b.max_quot_size),
+        ("sigma_comm_0", same_comm(&x.sigma_comm0, &y.sigma_comm0)),
+        ("sigma_comm_1", same_comm(&x.sigma_comm1, &y.sigma_comm1)),
+        ("sigma_comm_2", same_comm(&x.sigma_comm2, &y.sigma_comm2)),
+        ("ql_comm", same_comm(&x.ql_comm, &y.ql_comm)),
+        ("qr_comm", same_comm(&x.qr_comm, &y.qr_comm)),
+        ("qo_comm", same_comm(&x.qo_comm, &y.qo_comm)),
+        ("qm_comm", same_comm(&x.qm_comm, &y.qm_comm)),
+        ("qc_comm", same_comm(&x.qc_comm, &y.qc_comm)),
+        ("rcm_comm_0", same_comm(&x.rcm_comm0, &y.rcm_comm0)),
+        ("rcm_comm_1", same_comm(&x.rcm_comm1, &y.rcm_comm1)),
+        ("rcm_comm_2", same_comm(&x.rcm_comm2, &y.rcm_comm2)),
+        ("psm_comm", same_comm(&x.psm_comm, &y.psm_comm)),
+        ("add_comm", same_comm(&x.add_comm, &y.add_comm)),
+        ("mul1_comm", same_comm(&x.mul1_comm, &y.mul1_comm)),
+        ("mul2_comm", same_comm(&x.mul2_comm, &y.mul2_comm)),
+        ("emul1_comm", same_comm(&x.emul1_comm, &y.emul1_comm)),
+        ("emul2_comm", same_comm(&x.emul2_comm, &y.emul2_comm)),
+        ("emul3_comm", same_comm(&x.emul3_comm, &y.emul3_comm)),
+        (
+            "shifts",
+            a.shifts.r == b.shifts.r && a.shifts.o == b.shifts.o,
+        ),
+    ];
+    fields
+        .iter()
+        .filter(|(_, same)| !same)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
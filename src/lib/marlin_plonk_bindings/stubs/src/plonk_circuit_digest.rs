@@ -0,0 +1,136 @@
+use algebra::{fields::PrimeField, ToBytes, Zero};
+use blake2::{Blake2s, Digest};
+use plonk_circuits::gate::{CircuitGate, Gate, GateType};
+use plonk_circuits::wires::{Col, Col::*, Wire};
+
+use crate::plonk_circuit_stats::kind_index;
+
+/* A digest of a circuit: its public-input count, the size of its domain, and the type, wiring and
+ * coefficients of each of its gates.
+ *
+ * The same digest is computed from a gate vector and from the constraint system of the index
+ * built from it. The constraint system pads the gates to the size of its domain with zero gates
+ * wired to themselves, so trailing gates of that form are left out of the digest. The size of the
+ * domain is kept in it, as it changes the index built from the gates.
+ *
+ *   tag      : "MINA-CIRCUIT-2"
+ *   public   : u64
+ *   d1       : u64, the size of the domain
+ *   gates    : u64, the number of gates
+ *   for each gate:
+ *     type   : u8, the index of its variant in `CamlPlonkGateType`
+ *     row    : u64
+ *     l, r, o: for each wire, its row as u64 and its column as u8
+ *     c      : u64, the number of coefficients, then each coefficient as written by `ToBytes`
+ *
+ * All integers are little-endian. */
+
+const TAG: &[u8] = b"MINA-CIRCUIT-2";
+
+pub struct CanonicalGate<'a, F> {
+    pub typ: &'a GateType,
+    pub row: usize,
+    pub wires: [Wire; 3],
+    pub c: &'a [F],
+}
+
+fn col_byte(col: &Col) -> u8 {
+    match col {
+        L => 0,
+        R => 1,
+        O => 2,
+    }
+}
+
+impl<'a, F: PrimeField> CanonicalGate<'a, F> {
+    fn is_padding(&self) -> bool {
+        let wired_to_itself = self
+            .wires
+            .iter()
+            .zip([L, R, O].iter())
+            .all(|(w, col)| w.row == self.row && col_byte(&w.col) == col_byte(col));
+        match self.typ {
+            GateType::Zero => wired_to_itself && self.c.iter().all(|c| c.is_zero()),
+            _ => false,
+        }
+    }
+}
+
+pub fn of_gates<F: PrimeField>(gates: &[Gate<F>]) -> Vec<CanonicalGate<F>> {
+    gates
+        .iter()
+        .map(|gate| CanonicalGate {
+            typ: &gate.typ,
+            row: gate.wires.row,
+            wires: [gate.wires.l, gate.wires.r, gate.wires.o],
+            c: &gate.c,
+        })
+        .collect()
+}
+
+/// Reads back the gates of a constraint system over a domain of size `n`, whose wires are
+/// positions in the witness.
+pub fn of_circuit_gates<F: PrimeField>(
+    gates: &[CircuitGate<F>],
+    n: usize,
+) -> Vec<CanonicalGate<F>> {
+    let wire = |p: usize| Wire {
+        row: p % n,
+        col: match p / n {
+            0 => L,
+            1 => R,
+            _ => O,
+        },
+    };
+    gates
+        .iter()
+        .map(|gate| CanonicalGate {
+            typ: &gate.typ,
+            row: gate.wires.l.0,
+            wires: [
+                wire(gate.wires.l.1),
+                wire(gate.wires.r.1),
+                wire(gate.wires.o.1),
+            ],
+            c: &gate.c,
+        })
+        .collect()
+}
+
+/// The gates up to the last one that is not padding.
+pub fn without_padding<'a, 'b, F: PrimeField>(
+    gates: &'b [CanonicalGate<'a, F>],
+) -> &'b [CanonicalGate<'a, F>] {
+    let len = gates
+        .iter()
+        .rposition(|gate| !gate.is_padding())
+        .map_or(0, |i| i + 1);
+    &gates[..len]
+}
+
+pub fn digest<F: PrimeField>(public: usize, d1: usize, gates: &[CanonicalGate<F>]) -> Vec<u8> {
+    let gates = without_padding(gates);
+
+    let mut hasher = Blake2s::new();
+    let mut buf = vec![];
+    hasher.update(TAG);
+    hasher.update(&(public as u64).to_le_bytes());
+    hasher.update(&(d1 as u64).to_le_bytes());
+    hasher.update(&(gates.len() as u64).to_le_bytes());
+    for gate in gates.iter() {
+        hasher.update(&[kind_index(gate.typ) as u8]);
+        hasher.update(&(gate.row as u64).to_le_bytes());
+        for w in gate.wires.iter() {
+            hasher.update(&(w.row as u64).to_le_bytes());
+            hasher.update(&[col_byte(&w.col)]);
+        }
+        hasher.update(&(gate.c.len() as u64).to_le_bytes());
+        for c in gate.c.iter() {
+            buf.clear();
+            // Writing to a `Vec` cannot fail.
+            c.write(&mut buf).unwrap();
+            hasher.update(&buf);
+        }
+    }
+    hasher.finalize().to_vec()
+}
@@ -0,0 +1,137 @@
+use plonk_circuits::gate::GateType;
+use std::collections::BTreeMap;
+
+use crate::plonk_gate::CamlPlonkGateType;
+
+/* The shape of a circuit: how many gates of each type it has, the domains it needs and how much
+ * room is left in them, and the cycles of its permutation.
+ *
+ * The positions of the permutation are numbered as in the witness: the `L` column, then the `R`
+ * column, then the `O` column, each of the size of the domain. */
+
+#[derive(ocaml::IntoValue, ocaml::FromValue)]
+pub struct CamlPlonkCircuitStats {
+    pub rows: ocaml::Int, // without the trailing padding, see `plonk_circuit_digest`
+    pub gate_counts: Vec<(CamlPlonkGateType, ocaml::Int)>, // for each gate type that occurs
+    pub public_inputs: ocaml::Int,
+    pub domain_d1_size: ocaml::Int,
+    pub domain_d4_size: ocaml::Int,
+    pub domain_d8_size: ocaml::Int,
+    pub headroom: ocaml::Int, // rows that can be added before the domain doubles
+    pub cycles: ocaml::Int,   // cycles of the permutation of length at least 2
+    pub cycle_lengths: Vec<(ocaml::Int, ocaml::Int)>, // (length, number of cycles), by length
+    pub broken_wires: ocaml::Int, // positions that are not on a cycle of the permutation
+    pub urs_depth_required: ocaml::Int, // to commit to the polynomials without splitting them
+    pub urs_depth: Option<ocaml::Int>,
+}
+
+// The order of the variants of `CamlPlonkGateType`.
+pub(crate) fn kind_index(typ: &GateType) -> usize {
+    match typ {
+        GateType::Zero => 0,
+        GateType::Generic => 1,
+        GateType::Poseidon => 2,
+        GateType::Add1 => 3,
+        GateType::Add2 => 4,
+        GateType::Vbmul1 => 5,
+        GateType::Vbmul2 => 6,
+        GateType::Vbmul3 => 7,
+        GateType::Endomul1 => 8,
+        GateType::Endomul2 => 9,
+        GateType::Endomul3 => 10,
+        GateType::Endomul4 => 11,
+    }
+}
+
+pub fn gate_counts<'a, I>(types: I) -> Vec<(CamlPlonkGateType, ocaml::Int)>
+where
+    I: Iterator<Item = &'a GateType>,
+{
+    let mut counts: BTreeMap<usize, (&'a GateType, ocaml::Int)> = BTreeMap::new();
+    for typ in types {
+        counts.entry(kind_index(typ)).or_insert((typ, 0)).1 += 1;
+    }
+    counts
+        .into_iter()
+        .map(|(_, (typ, count))| (typ.into(), count))
+        .collect()
+}
+
+pub struct Cycles {
+    pub cycles: usize,
+    pub lengths: Vec<(usize, usize)>,
+    pub broken: usize,
+}
+
+/// Decomposes the permutation `succ` of the positions `0..positions` into cycles. If `succ` is
+/// not a permutation, the positions that don't lie on a cycle are counted as broken.
+pub fn cycles<F: Fn(usize) -> Option<usize>>(positions: usize, succ: F) -> Cycles {
+    let mut visited = vec![false; positions];
+    let mut lengths: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut broken = 0;
+    for start in 0..positions {
+        if visited[start] {
+            continue;
+        }
+        let mut path = vec![start];
+        visited[start] = true;
+        let mut p = start;
+        let closed = loop {
+            match succ(p) {
+                Some(next) if next == start => break true,
+                Some(next) if next < positions && !visited[next] => {
+                    visited[next] = true;
+                    path.push(next);
+                    p = next;
+                }
+                _ => break false,
+            }
+        };
+        if !closed {
+            broken += path.len();
+        } else if path.len() > 1 {
+            *lengths.entry(path.len()).or_insert(0) += 1;
+        }
+    }
+    Cycles {
+        cycles: lengths.values().sum(),
+        lengths: lengths.into_iter().collect(),
+        broken,
+    }
+}
+
+pub struct Shape {
+    pub rows: usize,
+    pub public: usize,
+    pub d1: usize,
+    pub d4: usize,
+    pub d8: usize,
+    pub urs_depth: Option<usize>,
+}
+
+pub fn stats(
+    shape: Shape,
+    gate_counts: Vec<(CamlPlonkGateType, ocaml::Int)>,
+    cycles: Cycles,
+) -> CamlPlonkCircuitStats {
+    CamlPlonkCircuitStats {
+        rows: shape.rows as ocaml::Int,
+        gate_counts,
+        public_inputs: shape.public as ocaml::Int,
+        domain_d1_size: shape.d1 as ocaml::Int,
+        domain_d4_size: shape.d4 as ocaml::Int,
+        domain_d8_size: shape.d8 as ocaml::Int,
+        headroom: shape.d1.saturating_sub(shape.rows) as ocaml::Int,
+        cycles: cycles.cycles as ocaml::Int,
+        cycle_lengths: cycles
+            .lengths
+            .into_iter()
+            .map(|(length, count)| (length as ocaml::Int, count as ocaml::Int))
+            .collect(),
+        broken_wires: cycles.broken as ocaml::Int,
+        // The largest polynomials committed to, other than the quotient, have degree less than
+        // the size of the domain.
+        urs_depth_required: shape.d1 as ocaml::Int,
+        urs_depth: shape.urs_depth.map(|depth| depth as ocaml::Int),
+    }
+}
@@ -0,0 +1,174 @@
+use algebra::{biginteger::BigInteger256, fields::PrimeField};
+use plonk_circuits::gate::Gate;
+use plonk_circuits::wires::{Col, Col::*, Wire};
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::plonk_witness_check::{decimal, kind_name};
+use crate::urs_container::write_atomically;
+
+/* Writing the gates of a circuit out, to inspect its wiring with other tools.
+ *
+ * As JSON, the gates are an array of objects
+ *
+ *   { "row": 0, "typ": "Generic",
+ *     "wires": { "l": { "row": 0, "col": "L" }, "r": ..., "o": ... },
+ *     "c": [ "1", "0", ... ] }
+ *
+ * with coefficients as decimal strings. As a Graphviz DOT graph, each row is a node labelled with
+ * its type, and each wire that is wired to another position is an edge from its row to the row it
+ * is wired to, labelled with the columns at both ends. Only the edges between exported rows are
+ * drawn. */
+
+#[derive(ocaml::IntoValue, ocaml::FromValue)]
+pub enum CamlPlonkRowSelection {
+    Range(ocaml::Int, ocaml::Int), // the rows from the first to the last, inclusive
+    Neighbourhood(ocaml::Int, ocaml::Int), // the rows within some number of wires of a row
+}
+
+fn col_name(col: &Col) -> &'static str {
+    match col {
+        L => "L",
+        R => "R",
+        O => "O",
+    }
+}
+
+fn wires<F>(gate: &Gate<F>) -> [(Col, Wire); 3] {
+    [(L, gate.wires.l), (R, gate.wires.r), (O, gate.wires.o)]
+}
+
+/// The rows selected by `selection`, in increasing order, or all of them. Returns `None` if the
+/// selection is not within the gates.
+pub fn select_rows<F>(
+    gates: &[Gate<F>],
+    selection: Option<&CamlPlonkRowSelection>,
+) -> Option<Vec<usize>> {
+    match selection {
+        None => Some((0..gates.len()).collect()),
+        Some(CamlPlonkRowSelection::Range(first, last)) => {
+            if *first < 0 || *first as usize >= gates.len() || last < first {
+                return None;
+            }
+            let last = std::cmp::min(*last as usize + 1, gates.len());
+            Some((*first as usize..last).collect())
+        }
+        Some(CamlPlonkRowSelection::Neighbourhood(row, depth)) => {
+            if *row < 0 || *row as usize >= gates.len() || *depth < 0 {
+                return None;
+            }
+            // The wires go both ways, as both ends hold the same variable.
+            let mut neighbours = vec![vec![]; gates.len()];
+            for (row, gate) in gates.iter().enumerate() {
+                for (_, target) in wires(gate).iter() {
+                    if target.row != row && target.row < gates.len() {
+                        neighbours[row].push(target.row);
+                        neighbours[target.row].push(row);
+                    }
+                }
+            }
+            let mut distance = vec![None; gates.len()];
+            let mut queue = VecDeque::new();
+            distance[*row as usize] = Some(0);
+            queue.push_back(*row as usize);
+            while let Some(row) = queue.pop_front() {
+                let d = distance[row].unwrap();
+                if d == *depth {
+                    continue;
+                }
+                for next in neighbours[row].iter() {
+                    if distance[*next].is_none() {
+                        distance[*next] = Some(d + 1);
+                        queue.push_back(*next);
+                    }
+                }
+            }
+            Some(
+                (0..gates.len())
+                    .filter(|row| distance[*row].is_some())
+                    .collect(),
+            )
+        }
+    }
+}
+
+pub fn write_json<F: PrimeField<BigInt = BigInteger256>>(
+    gates: &[Gate<F>],
+    rows: &[usize],
+    path: &Path,
+) -> io::Result<()> {
+    write_atomically(path, |file| {
+        writeln!(file, "[")?;
+        for (i, row) in rows.iter().enumerate() {
+            let gate = &gates[*row];
+            let wire = |w: &Wire| {
+                format!(
+                    "{{ \"row\": {}, \"col\": \"{}\" }}",
+                    w.row,
+                    col_name(&w.col)
+                )
+            };
+            let c: Vec<String> = gate
+                .c
+                .iter()
+                .map(|c| format!("\"{}\"", decimal(c)))
+                .collect();
+            write!(
+                file,
+                "  {{ \"row\": {}, \"typ\": \"{}\", ",
+                row,
+                kind_name(&gate.typ)
+            )?;
+            write!(
+                file,
+                "\"wires\": {{ \"l\": {}, \"r\": {}, \"o\": {} }}, ",
+                wire(&gate.wires.l),
+                wire(&gate.wires.r),
+                wire(&gate.wires.o)
+            )?;
+            let sep = if i + 1 < rows.len() { "," } else { "" };
+            writeln!(file, "\"c\": [{}] }}{}", c.join(", "), sep)?;
+        }
+        writeln!(file, "]")
+    })
+}
+
+pub fn write_dot<F>(gates: &[Gate<F>], rows: &[usize], path: &Path) -> io::Result<()> {
+    let mut selected = vec![false; gates.len()];
+    for row in rows.iter() {
+        selected[*row] = true;
+    }
+    write_atomically(path, |file| {
+        writeln!(file, "digraph circuit {{")?;
+        writeln!(file, "  node [shape=box];")?;
+        for row in rows.iter() {
+            writeln!(
+                file,
+                "  r{} [label=\"{}: {}\"];",
+                row,
+                row,
+                kind_name(&gates[*row].typ)
+            )?;
+        }
+        for row in rows.iter() {
+            for (col, target) in wires(&gates[*row]).iter() {
+                let to_itself = target.row == *row && col_name(col) == col_name(&target.col);
+                if to_itself || target.row >= gates.len() || !selected[target.row] {
+                    continue;
+                }
+                writeln!(
+                    file,
+                    "  r{} -> r{} [label=\"{} -> {}\"];",
+                    row,
+                    target.row,
+                    col_name(col),
+                    col_name(&target.col)
+                )?;
+            }
+        }
+        writeln!(file, "}}")
+    })
+}
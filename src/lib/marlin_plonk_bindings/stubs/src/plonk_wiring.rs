@@ -0,0 +1,152 @@
+use algebra::FftField;
+use plonk_circuits::gate::Gate;
+use plonk_circuits::wires::{Col::*, Wire};
+use std::{collections::HashMap, fmt};
+
+/* Copy constraints declared as equivalences between wire positions.
+ *
+ * Each position `(row, col)` of the circuit is a node of a union-find structure, and declaring
+ * that two positions hold the same variable merges their classes. When the circuit is
+ * finalised, the positions of each class are linked into a single cycle of the permutation, in
+ * increasing order of row and then column, with the last position wired back to the first.
+ * Positions that were never connected to another position keep the wires they were given. */
+
+// A position `(row, col)`, with the columns numbered in the order `L`, `R`, `O`.
+type Position = (usize, usize);
+
+fn position(wire: &Wire) -> Position {
+    let col = match wire.col {
+        L => 0,
+        R => 1,
+        O => 2,
+    };
+    (wire.row, col)
+}
+
+fn wire_of_position((row, col): Position) -> Wire {
+    Wire {
+        row,
+        col: match col {
+            0 => L,
+            1 => R,
+            _ => O,
+        },
+    }
+}
+
+pub enum WiringError {
+    RowOutOfRange { row: usize, rows: usize },
+}
+
+impl fmt::Display for WiringError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WiringError::RowOutOfRange { row, rows } => write!(
+                f,
+                "wiring: row {} is connected but the circuit has {} rows",
+                row, rows
+            ),
+        }
+    }
+}
+
+// ocaml-rs raises `Failure` with the `Debug` representation of an error, so make it readable.
+impl fmt::Debug for WiringError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for WiringError {}
+
+// The positions are kept in maps rather than vectors indexed by position, as rows are only
+// checked against the number of gates when the wiring is applied, and a single connection to a
+// far away row must not allocate the rows before it.
+#[derive(Default)]
+pub struct Wiring {
+    parent: HashMap<Position, Position>,
+    size: HashMap<Position, usize>,
+}
+
+impl Wiring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn find(&mut self, mut p: Position) -> Position {
+        let mut root = p;
+        while let Some(parent) = self.parent.get(&root).copied().filter(|q| *q != root) {
+            root = parent;
+        }
+        while p != root {
+            let next = self.parent[&p];
+            self.parent.insert(p, root);
+            p = next;
+        }
+        root
+    }
+
+    /// Declares that the positions `a` and `b` hold the same variable.
+    pub fn connect(&mut self, a: &Wire, b: &Wire) {
+        let (a, b) = (position(a), position(b));
+        for p in [a, b].iter() {
+            self.parent.entry(*p).or_insert(*p);
+            self.size.entry(*p).or_insert(1);
+        }
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        let (small, large) = if self.size[&a] < self.size[&b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent.insert(small, large);
+        let size = self.size[&small];
+        *self.size.get_mut(&large).unwrap() += size;
+    }
+
+    /// The classes of at least two positions, each in increasing order.
+    pub fn classes(&mut self) -> Vec<Vec<Wire>> {
+        let mut positions: Vec<Position> = self.parent.keys().copied().collect();
+        positions.sort();
+        let mut members: HashMap<Position, Vec<Position>> = HashMap::new();
+        for p in positions {
+            let root = self.find(p);
+            members.entry(root).or_insert_with(Vec::new).push(p);
+        }
+        let mut classes: Vec<Vec<Position>> = members
+            .into_iter()
+            .map(|(_, class)| class)
+            .filter(|class| class.len() > 1)
+            .collect();
+        classes.sort();
+        classes
+            .into_iter()
+            .map(|class| class.into_iter().map(wire_of_position).collect())
+            .collect()
+    }
+
+    /// Rewires `gates` so that each class of positions forms a cycle of the permutation. This
+    /// only depends on the declared equivalences, so applying it again has no further effect.
+    pub fn apply<F: FftField>(&mut self, gates: &mut [Gate<F>]) -> Result<(), WiringError> {
+        let rows = gates.len();
+        let classes = self.classes();
+        if let Some(w) = classes.iter().flatten().find(|w| w.row >= rows) {
+            return Err(WiringError::RowOutOfRange { row: w.row, rows });
+        }
+        for class in classes {
+            for (i, w) in class.iter().enumerate() {
+                let next = class[(i + 1) % class.len()];
+                let wires = &mut gates[w.row].wires;
+                match w.col {
+                    L => wires.l = next,
+                    R => wires.r = next,
+                    O => wires.o = next,
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+use algebra::{biginteger::BigInteger256, fields::PrimeField, Zero};
+use ff_fft::{EvaluationDomain, Radix2EvaluationDomain as Domain};
+use plonk_circuits::gate::{Gate, GateType};
+use plonk_circuits::wires::{Col, Col::*, Wire};
+use std::{
+    fmt,
+    io::{self, Write},
+    ops::Range,
+    path::Path,
+};
+
+use crate::bigint_256;
+use crate::plonk_gate::{CamlPlonkCol, CamlPlonkGateType};
+use crate::urs_container::write_atomically;
+
+/* Checking a witness against a circuit before proving, to find the rows that don't hold.
+ *
+ * The witness is laid out as the prover expects it: the `L` column, then the `R` column, then
+ * the `O` column, each padded to the size `n` of the domain of the circuit. Every gate's wires
+ * are checked against the copy constraints, and the arithmetic constraint of generic gates is
+ * evaluated as `c_l l + c_r r + c_o o + c_m l r + c_c`, less `l` on the public input rows, so
+ * that its value can be reported. The constraints of the other gate types span several rows and
+ * are checked by the constraint system of the circuit, which only tells whether they hold. */
+
+pub enum WitnessCheckError {
+    Io(io::Error),
+    NoDomain {
+        rows: usize,
+    },
+    WitnessLength {
+        expected: usize,
+        found: usize,
+    },
+    WireOutOfRange {
+        row: usize,
+        target: usize,
+        rows: usize,
+    },
+}
+
+impl fmt::Display for WitnessCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WitnessCheckError::Io(e) => write!(f, "witness check: {}", e),
+            WitnessCheckError::NoDomain { rows } => {
+                write!(f, "witness check: no domain for a circuit of {} rows", rows)
+            }
+            WitnessCheckError::WitnessLength { expected, found } => write!(
+                f,
+                "witness check: expected a witness of length {}, found {}",
+                expected, found
+            ),
+            WitnessCheckError::WireOutOfRange { row, target, rows } => write!(
+                f,
+                "witness check: row {} is wired to row {}, beyond the {} rows of the domain",
+                row, target, rows
+            ),
+        }
+    }
+}
+
+// ocaml-rs raises `Failure` with the `Debug` representation of an error, so make it readable.
+impl fmt::Debug for WitnessCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for WitnessCheckError {}
+
+impl From<io::Error> for WitnessCheckError {
+    fn from(e: io::Error) -> Self {
+        WitnessCheckError::Io(e)
+    }
+}
+
+#[derive(ocaml::IntoValue, ocaml::FromValue)]
+pub struct CamlWitnessFailure<F> {
+    pub row: ocaml::Int,
+    pub kind: CamlPlonkGateType,
+    pub l: F,
+    pub r: F,
+    pub o: F,
+    pub holds: bool,         // whether the constraint of the gate holds
+    pub residual: Option<F>, // the value of a failing generic constraint
+    pub copies: Vec<CamlPlonkCol>, // the wires whose value differs from the one they're wired to
+}
+
+#[derive(ocaml::IntoValue, ocaml::FromValue)]
+pub struct CamlWitnessReport<F> {
+    pub failures: Vec<CamlWitnessFailure<F>>, // the first failing rows, in order
+    pub num_failures: ocaml::Int,             // the number of failing rows in total
+}
+
+/// The size of the domain of a circuit of `rows` gates, as the index uses it.
+pub fn domain_size<F: PrimeField>(rows: usize) -> Result<usize, WitnessCheckError> {
+    Domain::<F>::compute_size_of_domain(rows).ok_or(WitnessCheckError::NoDomain { rows })
+}
+
+/// The index of the wire `w` in the witness, for a domain of size `n`.
+pub fn position(n: usize, w: &Wire) -> usize {
+    match w.col {
+        L => w.row,
+        R => w.row + n,
+        O => w.row + 2 * n,
+    }
+}
+
+pub(crate) fn kind_name(typ: &GateType) -> &'static str {
+    match typ {
+        GateType::Zero => "Zero",
+        GateType::Generic => "Generic",
+        GateType::Poseidon => "Poseidon",
+        GateType::Add1 => "Add1",
+        GateType::Add2 => "Add2",
+        GateType::Vbmul1 => "Vbmul1",
+        GateType::Vbmul2 => "Vbmul2",
+        GateType::Vbmul3 => "Vbmul3",
+        GateType::Endomul1 => "Endomul1",
+        GateType::Endomul2 => "Endomul2",
+        GateType::Endomul3 => "Endomul3",
+        GateType::Endomul4 => "Endomul4",
+    }
+}
+
+pub(crate) fn decimal<F: PrimeField<BigInt = BigInteger256>>(x: &F) -> String {
+    bigint_256::to_biguint(&x.into_repr()).to_string()
+}
+
+fn check_length<F>(n: usize, witness: &[F]) -> Result<(), WitnessCheckError> {
+    if witness.len() != 3 * n {
+        return Err(WitnessCheckError::WitnessLength {
+            expected: 3 * n,
+            found: witness.len(),
+        });
+    }
+    Ok(())
+}
+
+fn generic_residual<F: PrimeField>(gate: &Gate<F>, public: bool, l: F, r: F, o: F) -> F {
+    let c = |i: usize| gate.c.get(i).cloned().unwrap_or_else(F::zero);
+    let res = c(0) * &l + &(c(1) * &r) + &(c(2) * &o) + &(c(3) * &l * &r) + &c(4);
+    if public {
+        res - &l
+    } else {
+        res
+    }
+}
+
+/// Checks `witness` against `gates`, where `verify` tells whether the constraint of the gate of a
+/// row other than a generic gate holds.
+pub fn check<F: PrimeField>(
+    gates: &[Gate<F>],
+    public: usize,
+    witness: &[F],
+    limit: usize,
+    verify: impl Fn(usize) -> bool,
+) -> Result<CamlWitnessReport<F>, WitnessCheckError> {
+    let n = domain_size::<F>(gates.len())?;
+    check_length(n, witness)?;
+
+    let mut failures = vec![];
+    let mut num_failures = 0;
+    for (row, gate) in gates.iter().enumerate() {
+        let at = |col: Col| witness[position(n, &Wire { row, col })];
+        let (l, r, o) = (at(L), at(R), at(O));
+
+        let mut copies = vec![];
+        for (col, target) in [(L, &gate.wires.l), (R, &gate.wires.r), (O, &gate.wires.o)].iter() {
+            if target.row >= n {
+                return Err(WitnessCheckError::WireOutOfRange {
+                    row,
+                    target: target.row,
+                    rows: n,
+                });
+            }
+            if at(*col) != witness[position(n, target)] {
+                copies.push(col.into());
+            }
+        }
+
+        let (holds, residual) = match gate.typ {
+            GateType::Generic => {
+                let residual = generic_residual(gate, row < public, l, r, o);
+                (residual.is_zero(), Some(residual).filter(|r| !r.is_zero()))
+            }
+            _ => (verify(row), None),
+        };
+
+        if !holds || !copies.is_empty() {
+            num_failures += 1;
+            if failures.len() < limit {
+                failures.push(CamlWitnessFailure {
+                    row: row as ocaml::Int,
+                    kind: (&gate.typ).into(),
+                    l,
+                    r,
+                    o,
+                    holds,
+                    residual,
+                    copies,
+                });
+            }
+        }
+    }
+    Ok(CamlWitnessReport {
+        failures,
+        num_failures,
+    })
+}
+
+/// Writes the gates and witness values of the rows in `rows` as CSV, with one line per row:
+/// its number and gate type, the values of its wires, where each wire is wired to, and the
+/// coefficients of the gate. Field elements are written in decimal.
+pub fn write_csv<F: PrimeField<BigInt = BigInteger256>>(
+    gates: &[Gate<F>],
+    witness: &[F],
+    rows: Range<usize>,
+    path: &Path,
+) -> Result<(), WitnessCheckError> {
+    let n = domain_size::<F>(gates.len())?;
+    check_length(n, witness)?;
+    let rows = std::cmp::min(rows.start, gates.len())..std::cmp::min(rows.end, gates.len());
+    let wire = |w: &Wire| {
+        let col = match w.col {
+            L => "L",
+            R => "R",
+            O => "O",
+        };
+        format!("{}:{}", w.row, col)
+    };
+
+    write_atomically(path, |file| {
+        writeln!(file, "row,kind,l,r,o,wire_l,wire_r,wire_o,coefficients")?;
+        for row in rows {
+            let gate = &gates[row];
+            let at = |col: Col| decimal(&witness[position(n, &Wire { row, col })]);
+            writeln!(
+                file,
+                "{},{},{},{},{},{},{},{},{}",
+                row,
+                kind_name(&gate.typ),
+                at(L),
+                at(R),
+                at(O),
+                wire(&gate.wires.l),
+                wire(&gate.wires.r),
+                wire(&gate.wires.o),
+                gate.c.iter().map(decimal).collect::<Vec<_>>().join(" ")
+            )?;
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
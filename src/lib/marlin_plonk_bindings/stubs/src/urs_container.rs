@@ -0,0 +1,207 @@
+use blake2::{Blake2s, Digest};
+use commitment_dlog::{commitment::CommitmentCurve, srs::SRS};
+use std::{
+    convert::TryInto,
+    fmt,
+    fs::{self, File},
+    io::{self, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/* A self-describing container around the raw `SRS::write` encoding.
+ *
+ *   magic    : 8 bytes, "MINA-URS"
+ *   version  : u32
+ *   curve    : u32, see `UrsCurve`
+ *   depth    : u64, the number of `g` points
+ *   length   : u64, the length of the payload in bytes
+ *   checksum : 32 bytes, the Blake2s digest of the payload
+ *   payload  : `length` bytes, as written by `SRS::write`
+ *
+ * All integers are little-endian. Files are written to a temporary file in the same directory
+ * and then renamed into place, so that readers never observe a partially written URS. */
+
+const MAGIC: [u8; 8] = *b"MINA-URS";
+const VERSION: u32 = 1;
+const CHECKSUM_BYTES: usize = 32;
+const HEADER_BYTES: usize = MAGIC.len() + 4 + 4 + 8 + 8 + CHECKSUM_BYTES;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum UrsCurve {
+    Vesta = 1,
+    Pallas = 2,
+}
+
+impl UrsCurve {
+    pub(crate) fn name(id: u32) -> &'static str {
+        match id {
+            1 => "vesta",
+            2 => "pallas",
+            _ => "unknown",
+        }
+    }
+}
+
+pub enum UrsFileError {
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u32),
+    WrongCurve { expected: UrsCurve, found: u32 },
+    Truncated { expected: u64, found: u64 },
+    ChecksumMismatch,
+    Malformed(String),
+}
+
+impl fmt::Display for UrsFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UrsFileError::Io(e) => write!(f, "URS file: {}", e),
+            UrsFileError::BadMagic => write!(f, "URS file: not a URS container (bad magic)"),
+            UrsFileError::UnsupportedVersion(v) => write!(
+                f,
+                "URS file: unsupported version {} (expected {})",
+                v, VERSION
+            ),
+            UrsFileError::WrongCurve { expected, found } => write!(
+                f,
+                "URS file: URS is for the {} curve, expected the {} curve",
+                UrsCurve::name(*found),
+                UrsCurve::name(*expected as u32)
+            ),
+            UrsFileError::Truncated { expected, found } => write!(
+                f,
+                "URS file: truncated, expected {} bytes but found {}",
+                expected, found
+            ),
+            UrsFileError::ChecksumMismatch => write!(f, "URS file: checksum mismatch"),
+            UrsFileError::Malformed(e) => write!(f, "URS file: malformed payload: {}", e),
+        }
+    }
+}
+
+// ocaml-rs raises `Failure` with the `Debug` representation of an error, so make it readable.
+impl fmt::Debug for UrsFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for UrsFileError {}
+
+impl From<io::Error> for UrsFileError {
+    fn from(e: io::Error) -> Self {
+        UrsFileError::Io(e)
+    }
+}
+
+// Unique within the process, so that concurrent writes from different threads, even to the same
+// path, don't share a temporary file.
+static TEMPORARY_FILES: AtomicUsize = AtomicUsize::new(0);
+
+fn temporary_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    let n = TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed);
+    name.push(format!(".tmp.{}.{}", std::process::id(), n));
+    path.with_file_name(name)
+}
+
+/// Writes a file through `f` into a temporary file next to `path`, and renames it into place
+/// once it has been synced.
+pub(crate) fn write_atomically<F>(path: &Path, f: F) -> io::Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+{
+    let tmp = temporary_path(path);
+    let res = (|| {
+        let mut file = BufWriter::new(File::create(&tmp)?);
+        f(&mut file)?;
+        file.flush()?;
+        file.get_ref().sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if res.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    res
+}
+
+pub fn write<G: CommitmentCurve>(
+    srs: &SRS<G>,
+    curve: UrsCurve,
+    path: &Path,
+) -> Result<(), UrsFileError> {
+    let mut payload = vec![];
+    srs.write(&mut payload)?;
+
+    write_atomically(path, |file| {
+        file.write_all(&MAGIC)?;
+        file.write_all(&VERSION.to_le_bytes())?;
+        file.write_all(&(curve as u32).to_le_bytes())?;
+        file.write_all(&(srs.g.len() as u64).to_le_bytes())?;
+        file.write_all(&(payload.len() as u64).to_le_bytes())?;
+        file.write_all(&Blake2s::digest(&payload))?;
+        file.write_all(&payload)
+    })?;
+    Ok(())
+}
+
+pub fn read<G: CommitmentCurve>(curve: UrsCurve, path: &Path) -> Result<SRS<G>, UrsFileError> {
+    let mut bytes = vec![];
+    File::open(path)?.read_to_end(&mut bytes)?;
+
+    if bytes.len() < MAGIC.len() {
+        return Err(UrsFileError::Truncated {
+            expected: HEADER_BYTES as u64,
+            found: bytes.len() as u64,
+        });
+    }
+    if bytes[..MAGIC.len()] != MAGIC {
+        return Err(UrsFileError::BadMagic);
+    }
+    if bytes.len() < HEADER_BYTES {
+        return Err(UrsFileError::Truncated {
+            expected: HEADER_BYTES as u64,
+            found: bytes.len() as u64,
+        });
+    }
+    let (header, payload) = bytes.split_at(HEADER_BYTES);
+    let u32_at = |i: usize| u32::from_le_bytes(header[i..i + 4].try_into().unwrap());
+    let u64_at = |i: usize| u64::from_le_bytes(header[i..i + 8].try_into().unwrap());
+
+    let version = u32_at(8);
+    if version != VERSION {
+        return Err(UrsFileError::UnsupportedVersion(version));
+    }
+    let found = u32_at(12);
+    if found != curve as u32 {
+        return Err(UrsFileError::WrongCurve {
+            expected: curve,
+            found,
+        });
+    }
+    let depth = u64_at(16);
+    let length = u64_at(24);
+    if (payload.len() as u64) < length {
+        return Err(UrsFileError::Truncated {
+            expected: HEADER_BYTES as u64 + length,
+            found: bytes.len() as u64,
+        });
+    }
+    if payload.len() as u64 != length {
+        return Err(UrsFileError::Malformed("trailing bytes".to_string()));
+    }
+    if Blake2s::digest(payload)[..] != header[32..32 + CHECKSUM_BYTES] {
+        return Err(UrsFileError::ChecksumMismatch);
+    }
+
+    let srs = SRS::<G>::read(payload).map_err(|e| UrsFileError::Malformed(e.to_string()))?;
+    if srs.g.len() as u64 != depth {
+        return Err(UrsFileError::Malformed(format!(
+            "expected depth {}, found {}",
+            depth,
+            srs.g.len()
+        )));
+    }
+    Ok(srs)
+}
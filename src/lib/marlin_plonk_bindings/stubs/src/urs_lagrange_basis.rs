@@ -0,0 +1,256 @@
+use algebra::{
+    curves::{AffineCurve, ProjectiveCurve},
+    fields::{Field, PrimeField},
+    One, Zero,
+};
+use blake2::{Blake2s, Digest};
+use commitment_dlog::{
+    commitment::{CommitmentCurve, PolyComm},
+    srs::SRS,
+};
+use ff_fft::{EvaluationDomain, Radix2EvaluationDomain as Domain};
+use rayon::prelude::*;
+use std::{
+    convert::TryInto,
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+};
+
+use crate::caml_urs::CamlUrs;
+use crate::urs_container::{write_atomically, UrsCurve, UrsFileError};
+use crate::urs_mmap::POINT_BYTES;
+use crate::urs_utils::urs_digest;
+
+/* Commitments to the Lagrange basis.
+ *
+ * The coefficients of the `i`th Lagrange basis polynomial of a domain of size `n` with generator
+ * `w` are `w^(-i j) / n`, so its commitment is `sum_j w^(-i j) / n * g_j`. This is the inverse
+ * FFT of the vector `g`, taken over the group, and gives the commitments for every `i` at once.
+ * When `n` is larger than the URS, each polynomial is committed to in chunks, and each chunk is
+ * the inverse FFT of `g` shifted to the chunk's offset. */
+
+fn bitreverse(mut n: usize, l: u32) -> usize {
+    let mut r = 0;
+    for _ in 0..l {
+        r = (r << 1) | (n & 1);
+        n >>= 1;
+    }
+    r
+}
+
+fn ifft<C: ProjectiveCurve>(a: &mut [C], domain: &Domain<C::ScalarField>) {
+    let n = a.len();
+    let log_n = domain.log_size_of_group;
+    for k in 0..n {
+        let rk = bitreverse(k, log_n);
+        if k < rk {
+            a.swap(k, rk);
+        }
+    }
+
+    let mut m = 1;
+    while m < n {
+        let w_m = domain.group_gen_inv.pow([(n / (2 * m)) as u64]);
+        let mut twiddles = Vec::with_capacity(m);
+        let mut w = C::ScalarField::one();
+        for _ in 0..m {
+            twiddles.push(w.into_repr());
+            w *= &w_m;
+        }
+        a.par_chunks_mut(2 * m).for_each(|chunk| {
+            let (lo, hi) = chunk.split_at_mut(m);
+            lo.par_iter_mut()
+                .zip(hi.par_iter_mut())
+                .zip(twiddles.par_iter())
+                .for_each(|((x, y), w)| {
+                    let t = y.mul(*w);
+                    *y = *x - &t;
+                    *x += &t;
+                });
+        });
+        m *= 2;
+    }
+
+    let size_inv = domain.size_inv.into_repr();
+    a.par_iter_mut().for_each(|x| *x = x.mul(size_inv));
+}
+
+/// Computes the commitments to every Lagrange basis polynomial of the domain of size
+/// `domain_size`, chunked as `SRS::commit_non_hiding` would. Returns `None` if there is no
+/// such domain.
+pub fn compute<G: CommitmentCurve>(srs: &SRS<G>, domain_size: usize) -> Option<Vec<PolyComm<G>>> {
+    let domain = Domain::<G::ScalarField>::new(domain_size)?;
+    let n = domain.size();
+    let depth = srs.g.len();
+    if n != domain_size || depth == 0 {
+        return None;
+    }
+    let num_chunks = (n + depth - 1) / depth;
+
+    let chunks: Vec<Vec<G>> = (0..num_chunks)
+        .map(|k| {
+            let mut v = vec![G::Projective::zero(); n];
+            let offset = k * depth;
+            let len = std::cmp::min(depth, n - offset);
+            v[offset..offset + len]
+                .par_iter_mut()
+                .zip(srs.g[..len].par_iter())
+                .for_each(|(x, g)| *x = g.into_projective());
+            ifft(&mut v, &domain);
+            G::Projective::batch_normalization_into_affine(&v)
+        })
+        .collect();
+
+    Some(
+        (0..n)
+            .map(|i| PolyComm {
+                unshifted: chunks.iter().map(|chunk| chunk[i]).collect(),
+                shifted: None,
+            })
+            .collect(),
+    )
+}
+
+/* The cached bases can be persisted to a file next to the URS.
+ *
+ *   magic    : 8 bytes, "MINA-ULB"
+ *   version  : u32
+ *   curve    : u32, see `UrsCurve`
+ *   urs      : 32 bytes, the digest of the URS, see `urs_utils::urs_digest`
+ *   count    : u64, the number of domains
+ *   checksum : 32 bytes, the Blake2s digest of the payload
+ *   payload  : for each domain, its size `n` and number of chunks `c` as u64s, followed by the
+ *              `c` chunks of each of the `n` commitments, as uncompressed points
+ *
+ * All integers are little-endian. */
+
+const MAGIC: [u8; 8] = *b"MINA-ULB";
+const VERSION: u32 = 2;
+const HEADER_BYTES: usize = MAGIC.len() + 4 + 4 + 32 + 8 + 32;
+
+pub fn write<G: CommitmentCurve>(
+    urs: &CamlUrs<G>,
+    curve: UrsCurve,
+    path: &Path,
+    encode: fn(&G) -> [u8; POINT_BYTES],
+) -> Result<(), UrsFileError> {
+    let sizes = urs.lagrange_basis_domain_sizes();
+    let mut payload = vec![];
+    for n in sizes.iter() {
+        let basis = urs.cached_lagrange_basis(*n).unwrap();
+        let num_chunks = basis.first().map_or(0, |c| c.unshifted.len());
+        payload.extend_from_slice(&(*n as u64).to_le_bytes());
+        payload.extend_from_slice(&(num_chunks as u64).to_le_bytes());
+        for comm in basis.iter() {
+            for g in comm.unshifted.iter() {
+                payload.extend_from_slice(&encode(g));
+            }
+        }
+    }
+
+    write_atomically(path, |file| {
+        file.write_all(&MAGIC)?;
+        file.write_all(&VERSION.to_le_bytes())?;
+        file.write_all(&(curve as u32).to_le_bytes())?;
+        file.write_all(&urs_digest(&urs.srs))?;
+        file.write_all(&(sizes.len() as u64).to_le_bytes())?;
+        file.write_all(&Blake2s::digest(&payload))?;
+        file.write_all(&payload)
+    })?;
+    Ok(())
+}
+
+/// Reads the bases persisted by `write` into the cache of `urs`. Fails if the file was written
+/// for a different URS.
+pub fn read<G: CommitmentCurve>(
+    urs: &CamlUrs<G>,
+    curve: UrsCurve,
+    path: &Path,
+    decode: fn(&[u8]) -> Option<G>,
+) -> Result<(), UrsFileError> {
+    let mut bytes = vec![];
+    File::open(path)?.read_to_end(&mut bytes)?;
+
+    if bytes.len() < MAGIC.len() {
+        return Err(UrsFileError::Truncated {
+            expected: HEADER_BYTES as u64,
+            found: bytes.len() as u64,
+        });
+    }
+    if bytes[..MAGIC.len()] != MAGIC {
+        return Err(UrsFileError::BadMagic);
+    }
+    if bytes.len() < HEADER_BYTES {
+        return Err(UrsFileError::Truncated {
+            expected: HEADER_BYTES as u64,
+            found: bytes.len() as u64,
+        });
+    }
+    let (header, payload) = bytes.split_at(HEADER_BYTES);
+
+    let version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+    if version != VERSION {
+        return Err(UrsFileError::UnsupportedVersion(version));
+    }
+    let found = u32::from_le_bytes(header[12..16].try_into().unwrap());
+    if found != curve as u32 {
+        return Err(UrsFileError::WrongCurve {
+            expected: curve,
+            found,
+        });
+    }
+    if header[16..48] != urs_digest(&urs.srs)[..] {
+        return Err(UrsFileError::Malformed(
+            "Lagrange bases were computed for a different URS".to_string(),
+        ));
+    }
+    let count = u64::from_le_bytes(header[48..56].try_into().unwrap());
+    if Blake2s::digest(payload)[..] != header[56..88] {
+        return Err(UrsFileError::ChecksumMismatch);
+    }
+
+    let depth = urs.srs.g.len();
+    let truncated = || UrsFileError::Malformed("payload ends early".to_string());
+    let mut bases = vec![];
+    let mut rest = payload;
+    for _ in 0..count {
+        if rest.len() < 16 {
+            return Err(truncated());
+        }
+        let n = u64::from_le_bytes(rest[0..8].try_into().unwrap()) as usize;
+        let num_chunks = u64::from_le_bytes(rest[8..16].try_into().unwrap()) as usize;
+        // The shape `compute` gives, so that a basis is never cached with missing chunks.
+        if !n.is_power_of_two() || depth == 0 || num_chunks != (n + depth - 1) / depth {
+            return Err(UrsFileError::Malformed(format!(
+                "a basis of {} commitments in {} chunks",
+                n, num_chunks
+            )));
+        }
+        let len = n
+            .checked_mul(num_chunks)
+            .and_then(|x| x.checked_mul(POINT_BYTES))
+            .ok_or_else(truncated)?;
+        if rest.len() < 16 + len {
+            return Err(truncated());
+        }
+        let points = rest[16..16 + len]
+            .par_chunks(POINT_BYTES)
+            .map(decode)
+            .collect::<Option<Vec<G>>>()
+            .ok_or_else(|| UrsFileError::Malformed("invalid point".to_string()))?;
+        let basis = points
+            .chunks(num_chunks)
+            .map(|chunks| PolyComm {
+                unshifted: chunks.to_vec(),
+                shifted: None,
+            })
+            .collect();
+        bases.push((n, basis));
+        rest = &rest[16 + len..];
+    }
+    for (n, basis) in bases.into_iter() {
+        urs.set_lagrange_basis(n, basis);
+    }
+    Ok(())
+}
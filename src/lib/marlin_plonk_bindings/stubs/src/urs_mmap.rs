@@ -0,0 +1,233 @@
+use commitment_dlog::{commitment::CommitmentCurve, srs::SRS};
+use memmap::Mmap;
+use rayon::prelude::*;
+use std::{
+    cell::RefCell,
+    convert::TryInto,
+    fs::File,
+    io::Write,
+    path::Path,
+    rc::Rc,
+    sync::{Arc, Condvar, Mutex},
+};
+
+use crate::urs_container::{write_atomically, UrsCurve, UrsFileError};
+
+/* A URS layout that can be memory-mapped and decoded lazily.
+ *
+ *   magic    : 8 bytes, "MINA-UMM"
+ *   version  : u32
+ *   curve    : u32, see `UrsCurve`
+ *   depth    : u64, the number of `g` points
+ *   reserved : 8 bytes, zero
+ *   h        : POINT_BYTES
+ *   g        : depth * POINT_BYTES
+ *
+ * Points use the uncompressed encoding of the curve, so that point `i` is at a fixed offset.
+ * There is no checksum, since checking it would require reading the whole file; instead every
+ * point is checked to be canonical and on the curve when it is decoded.
+ *
+ * Opening a file only checks its header. All of its points are then checked on a background
+ * thread, while callers decode just the points they use: committing to a polynomial of `n`
+ * coefficients decodes the first `n` points, once. */
+
+const MAGIC: [u8; 8] = *b"MINA-UMM";
+const VERSION: u32 = 1;
+const HEADER_BYTES: usize = 32;
+pub const POINT_BYTES: usize = 64;
+
+struct Check {
+    result: Mutex<Option<Result<(), String>>>,
+    done: Condvar,
+}
+
+pub struct MappedUrs<G> {
+    map: Arc<Mmap>,
+    depth: usize,
+    decode: fn(&[u8]) -> Option<G>,
+    check: Arc<Check>,
+    prefix: RefCell<Option<Rc<SRS<G>>>>, // the first points, as decoded so far
+}
+
+fn invalid_point(i: usize) -> UrsFileError {
+    UrsFileError::Malformed(format!("invalid point at index {}", i))
+}
+
+pub fn write<G: CommitmentCurve>(
+    srs: &SRS<G>,
+    curve: UrsCurve,
+    path: &Path,
+    encode: fn(&G) -> [u8; POINT_BYTES],
+) -> Result<(), UrsFileError> {
+    write_atomically(path, |file| {
+        file.write_all(&MAGIC)?;
+        file.write_all(&VERSION.to_le_bytes())?;
+        file.write_all(&(curve as u32).to_le_bytes())?;
+        file.write_all(&(srs.g.len() as u64).to_le_bytes())?;
+        file.write_all(&[0u8; 8])?;
+        file.write_all(&encode(&srs.h))?;
+        for g in srs.g.iter() {
+            file.write_all(&encode(g))?;
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+// Checks every point, in parallel, and reports the first one that is not valid.
+fn check_points<G>(map: &Mmap, decode: fn(&[u8]) -> Option<G>) -> Result<(), String> {
+    if decode(&map[HEADER_BYTES..HEADER_BYTES + POINT_BYTES]).is_none() {
+        return Err("invalid point h".to_string());
+    }
+    match map[HEADER_BYTES + POINT_BYTES..]
+        .par_chunks(POINT_BYTES)
+        .enumerate()
+        .find_first(|(_, bytes)| decode(bytes).is_none())
+    {
+        Some((i, _)) => Err(format!("invalid point at index {}", i)),
+        None => Ok(()),
+    }
+}
+
+impl<G: CommitmentCurve + Send + 'static> MappedUrs<G> {
+    /// Maps the file at `path`, checking only its header and length, and starts checking its
+    /// points in the background.
+    pub fn open(
+        curve: UrsCurve,
+        path: &Path,
+        decode: fn(&[u8]) -> Option<G>,
+    ) -> Result<Self, UrsFileError> {
+        let file = File::open(path)?;
+        // The mapping is read-only. As with any mapped file, it must not be truncated while in
+        // use; URS files are only ever replaced by renaming.
+        let map = unsafe { Mmap::map(&file)? };
+
+        if map.len() >= MAGIC.len() && map[..MAGIC.len()] != MAGIC {
+            return Err(UrsFileError::BadMagic);
+        }
+        if map.len() < HEADER_BYTES {
+            return Err(UrsFileError::Truncated {
+                expected: HEADER_BYTES as u64,
+                found: map.len() as u64,
+            });
+        }
+        let version = u32::from_le_bytes(map[8..12].try_into().unwrap());
+        if version != VERSION {
+            return Err(UrsFileError::UnsupportedVersion(version));
+        }
+        let found = u32::from_le_bytes(map[12..16].try_into().unwrap());
+        if found != curve as u32 {
+            return Err(UrsFileError::WrongCurve {
+                expected: curve,
+                found,
+            });
+        }
+        let depth = u64::from_le_bytes(map[16..24].try_into().unwrap());
+        let expected = depth
+            .checked_add(1)
+            .and_then(|n| n.checked_mul(POINT_BYTES as u64))
+            .and_then(|n| n.checked_add(HEADER_BYTES as u64))
+            .ok_or_else(|| UrsFileError::Malformed(format!("invalid depth {}", depth)))?;
+        if map.len() as u64 != expected {
+            return Err(UrsFileError::Truncated {
+                expected,
+                found: map.len() as u64,
+            });
+        }
+        let map = Arc::new(map);
+        let check = Arc::new(Check {
+            result: Mutex::new(None),
+            done: Condvar::new(),
+        });
+        {
+            let (map, check) = (Arc::clone(&map), Arc::clone(&check));
+            std::thread::spawn(move || {
+                let result = check_points(&map, decode);
+                *check.result.lock().unwrap() = Some(result);
+                check.done.notify_all();
+            });
+        }
+        Ok(MappedUrs {
+            map,
+            depth: depth as usize,
+            decode,
+            check,
+            prefix: RefCell::new(None),
+        })
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    fn g_bytes(&self) -> &[u8] {
+        &self.map[HEADER_BYTES + POINT_BYTES..]
+    }
+
+    pub fn h(&self) -> Result<G, UrsFileError> {
+        (self.decode)(&self.map[HEADER_BYTES..HEADER_BYTES + POINT_BYTES])
+            .ok_or_else(|| UrsFileError::Malformed("invalid point h".to_string()))
+    }
+
+    /// Decodes the `i`th `g` point. Returns `None` if `i` is out of range.
+    pub fn g(&self, i: usize) -> Option<Result<G, UrsFileError>> {
+        if i >= self.depth {
+            return None;
+        }
+        let bytes = &self.g_bytes()[i * POINT_BYTES..(i + 1) * POINT_BYTES];
+        Some((self.decode)(bytes).ok_or_else(|| invalid_point(i)))
+    }
+
+    /// The result of checking every point, or `None` while the check is still running.
+    pub fn checked(&self) -> Option<Result<(), UrsFileError>> {
+        let result = self.check.result.lock().unwrap();
+        result
+            .as_ref()
+            .map(|r| r.clone().map_err(UrsFileError::Malformed))
+    }
+
+    /// Waits for the check of every point to finish, and returns its result.
+    pub fn wait_checked(&self) -> Result<(), UrsFileError> {
+        let mut result = self.check.result.lock().unwrap();
+        while result.is_none() {
+            result = self.check.done.wait(result).unwrap();
+        }
+        result
+            .as_ref()
+            .unwrap()
+            .clone()
+            .map_err(UrsFileError::Malformed)
+    }
+
+    /// A URS made of the first `n` points, at most `depth`. Points are decoded, in parallel, the
+    /// first time they are needed.
+    pub fn prefix(&self, n: usize) -> Result<Rc<SRS<G>>, UrsFileError> {
+        let n = std::cmp::min(n, self.depth);
+        let mut prefix = self.prefix.borrow_mut();
+        if let Some(srs) = prefix.as_ref() {
+            if srs.g.len() >= n {
+                return Ok(Rc::clone(srs));
+            }
+        }
+        let decoded = prefix.as_ref().map_or(0, |srs| srs.g.len());
+        let decode = self.decode;
+        let mut g: Vec<G> = prefix.as_ref().map_or(vec![], |srs| srs.g.clone());
+        let rest: Vec<G> = self.g_bytes()[decoded * POINT_BYTES..n * POINT_BYTES]
+            .par_chunks(POINT_BYTES)
+            .enumerate()
+            .map(|(i, bytes)| decode(bytes).ok_or_else(|| invalid_point(decoded + i)))
+            .collect::<Result<_, _>>()?;
+        g.extend(rest);
+        let mut srs = SRS::<G>::create(0);
+        srs.g = g;
+        srs.h = self.h()?;
+        let srs = Rc::new(srs);
+        *prefix = Some(Rc::clone(&srs));
+        Ok(srs)
+    }
+
+    /// Decodes every point into an in-memory URS, as needed to create an index.
+    pub fn to_srs(&self) -> Result<Rc<SRS<G>>, UrsFileError> {
+        self.prefix(self.depth)
+    }
+}
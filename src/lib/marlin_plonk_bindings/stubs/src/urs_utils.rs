@@ -1,29 +1,158 @@
-use algebra::{fields::PrimeField, One, ProjectiveCurve, UniformRand, VariableBaseMSM, Zero};
+use algebra::{
+    biginteger::BigInteger,
+    fields::{Field, PrimeField},
+    One, ProjectiveCurve, ToBytes, UniformRand, VariableBaseMSM, Zero,
+};
+use blake2::{Blake2b, Blake2s, Digest};
+use rand::seq::index::sample;
+use rand_core::RngCore;
+use std::{fmt, ops::Range};
 
 use commitment_dlog::{
-    commitment::{b_poly_coefficients, CommitmentCurve},
+    commitment::{b_poly_coefficients, CommitmentCurve, PolyComm},
     srs::SRS,
 };
+use ff_fft::DensePolynomial;
+use groupmap::GroupMap;
 use rayon::prelude::*;
 
-// TODO: Not compatible with variable rounds
-pub fn batch_dlog_accumulator_check<G: CommitmentCurve>(
+/* Batched checks of dlog accumulators. Each accumulator is a commitment to the polynomial
+ * `b(X) = prod_i (1 + chal_i X^(2^(rounds - 1 - i)))` of its challenges, so an accumulator with
+ * `rounds` challenges has `2^rounds` coefficients and fits in a URS of at least that depth. The
+ * challenges of all of the accumulators are passed concatenated, with the number of challenges
+ * of each accumulator in `rounds`. */
+
+pub enum AccumulatorError {
+    UnevenChallenges {
+        comms: usize,
+        chals: usize,
+    },
+    RoundsLength {
+        comms: usize,
+        rounds: usize,
+    },
+    ChallengesLength {
+        expected: usize,
+        found: usize,
+    },
+    TooManyRounds {
+        index: usize,
+        rounds: usize,
+        depth: usize,
+    },
+}
+
+impl fmt::Display for AccumulatorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AccumulatorError::UnevenChallenges { comms, chals } => write!(
+                f,
+                "batch accumulator: {} challenges can't be split evenly between {} accumulators",
+                chals, comms
+            ),
+            AccumulatorError::RoundsLength { comms, rounds } => write!(
+                f,
+                "batch accumulator: {} round counts given for {} accumulators",
+                rounds, comms
+            ),
+            AccumulatorError::ChallengesLength { expected, found } => write!(
+                f,
+                "batch accumulator: expected {} challenges in total, found {}",
+                expected, found
+            ),
+            AccumulatorError::TooManyRounds {
+                index,
+                rounds,
+                depth,
+            } => write!(
+                f,
+                "batch accumulator: accumulator {} has {} rounds, too many for a URS of depth {}",
+                index, rounds, depth
+            ),
+        }
+    }
+}
+
+// ocaml-rs raises `Failure` with the `Debug` representation of an error, so make it readable.
+impl fmt::Debug for AccumulatorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for AccumulatorError {}
+
+/// Splits `chals` into the challenges of each of the `num_comms` accumulators. Without
+/// `rounds`, every accumulator gets the same number of challenges.
+fn split_challenges<'a, F>(
+    depth: usize,
+    num_comms: usize,
+    rounds: Option<&[usize]>,
+    chals: &'a [F],
+) -> Result<Vec<&'a [F]>, AccumulatorError> {
+    let rounds = match rounds {
+        Some(rounds) => {
+            if rounds.len() != num_comms {
+                return Err(AccumulatorError::RoundsLength {
+                    comms: num_comms,
+                    rounds: rounds.len(),
+                });
+            }
+            rounds.to_vec()
+        }
+        None if num_comms == 0 && chals.is_empty() => vec![],
+        None if num_comms == 0 || chals.len() % num_comms != 0 => {
+            return Err(AccumulatorError::UnevenChallenges {
+                comms: num_comms,
+                chals: chals.len(),
+            })
+        }
+        None => vec![chals.len() / num_comms; num_comms],
+    };
+
+    let expected = rounds
+        .iter()
+        .try_fold(0usize, |acc, r| acc.checked_add(*r))
+        .unwrap_or(usize::MAX);
+    if expected != chals.len() {
+        return Err(AccumulatorError::ChallengesLength {
+            expected,
+            found: chals.len(),
+        });
+    }
+    let mut res = Vec::with_capacity(rounds.len());
+    let mut rest = chals;
+    for (index, r) in rounds.into_iter().enumerate() {
+        if r >= 8 * std::mem::size_of::<usize>() || (1 << r) > depth {
+            return Err(AccumulatorError::TooManyRounds {
+                index,
+                rounds: r,
+                depth,
+            });
+        }
+        let (chals, tail) = rest.split_at(r);
+        res.push(chals);
+        rest = tail;
+    }
+    Ok(res)
+}
+
+// Checks that `sum_i r^i (comms[i] - <b_i, g>) = 0` for the accumulators in `range`, for a
+// random `r`. `b_polys[i]` holds the coefficients of the `b` polynomial of accumulator `i`.
+fn random_combination_vanishes<G: CommitmentCurve, R: RngCore>(
     urs: &SRS<G>,
-    comms: &Vec<G>,
-    chals: &Vec<G::ScalarField>,
+    comms: &[G],
+    b_polys: &[Vec<G::ScalarField>],
+    range: Range<usize>,
+    rng: &mut R,
 ) -> bool {
-    let k = comms.len();
-
+    let k = range.len();
     if k == 0 {
-        assert_eq!(chals.len(), 0);
         return true;
     }
 
-    let rounds = chals.len() / k;
-    assert_eq!(chals.len() % rounds, 0);
-
     let rs = {
-        let r = G::ScalarField::rand(&mut rand_core::OsRng);
+        let r = G::ScalarField::rand(rng);
         let mut rs = vec![G::ScalarField::one(); k];
         for i in 1..k {
             rs[i] = r * &rs[i - 1];
@@ -31,71 +160,249 @@ pub fn batch_dlog_accumulator_check<G: CommitmentCurve>(
         rs
     };
 
-    let mut points = urs.g.clone();
-    let n = points.len();
-    points.extend(comms);
-
+    // An accumulator with fewer rounds only involves a prefix of `g`. The points are borrowed
+    // from the URS rather than copied, as the bisection checks many ranges.
+    let n = b_polys[range.clone()].iter().map(|b| b.len()).max().unwrap_or(0);
     let mut scalars = vec![G::ScalarField::zero(); n];
-    scalars.extend(&rs[..]);
 
-    let chal_invs = {
-        let mut cs = chals.clone();
-        algebra::fields::batch_inversion(&mut cs);
-        cs
-    };
-
-    let termss: Vec<_> = chals
+    let termss: Vec<_> = b_polys[range.clone()]
         .par_iter()
-        .zip(chal_invs)
-        .chunks(rounds)
-        .zip(rs)
-        .map(|(chunk, r)| {
-            let chals: Vec<_> = chunk.iter().map(|(c, _)| **c).collect();
-            let mut s = b_poly_coefficients(&chals);
-            s.iter_mut().for_each(|c| *c *= &r);
-            s
-        })
+        .zip(rs.par_iter())
+        .map(|(b, r)| b.iter().map(|c| *c * r).collect::<Vec<_>>())
         .collect();
 
     for terms in termss {
-        assert_eq!(terms.len(), n);
-        for i in 0..n {
-            scalars[i] -= &terms[i];
+        for (scalar, term) in scalars.iter_mut().zip(terms.iter()) {
+            *scalar -= term;
         }
     }
 
     let scalars: Vec<_> = scalars.iter().map(|x| x.into_repr()).collect();
-    VariableBaseMSM::multi_scalar_mul(&points, &scalars) == G::Projective::zero()
+    let rs: Vec<_> = rs.iter().map(|x| x.into_repr()).collect();
+    VariableBaseMSM::multi_scalar_mul(&urs.g[..n], &scalars)
+        + VariableBaseMSM::multi_scalar_mul(&comms[range], &rs)
+        == G::Projective::zero()
 }
 
-pub fn batch_dlog_accumulator_generate<G: CommitmentCurve>(
+fn b_polys_of<F: Field>(chunks: Vec<&[F]>) -> Vec<Vec<F>> {
+    chunks
+        .into_par_iter()
+        .map(|chals| b_poly_coefficients(chals))
+        .collect()
+}
+
+pub fn batch_dlog_accumulator_check<G: CommitmentCurve, R: RngCore>(
     urs: &SRS<G>,
-    num_comms: usize,
+    comms: &Vec<G>,
+    rounds: Option<&[usize]>,
     chals: &Vec<G::ScalarField>,
-) -> Vec<G> {
-    let k = num_comms;
+    rng: &mut R,
+) -> Result<bool, AccumulatorError> {
+    let chunks = split_challenges(urs.g.len(), comms.len(), rounds, chals)?;
+    let b_polys = b_polys_of(chunks);
+    Ok(random_combination_vanishes(
+        urs,
+        comms,
+        &b_polys,
+        0..comms.len(),
+        rng,
+    ))
+}
 
-    if k == 0 {
-        assert_eq!(chals.len(), 0);
-        return vec![];
+/// Returns the indices of the accumulators that fail the check, in increasing order.
+///
+/// The batch is bisected: a range whose random combination vanishes is accepted as a whole, and
+/// one that does not is split in two. When the first half of a failing range passes, the second
+/// half is known to fail without checking it. With `f` failing accumulators out of `k`, this
+/// takes `O(f log k)` multi-scalar multiplications instead of `k`.
+pub fn batch_dlog_accumulator_failures<G: CommitmentCurve, R: RngCore>(
+    urs: &SRS<G>,
+    comms: &Vec<G>,
+    rounds: Option<&[usize]>,
+    chals: &Vec<G::ScalarField>,
+    rng: &mut R,
+) -> Result<Vec<usize>, AccumulatorError> {
+    let chunks = split_challenges(urs.g.len(), comms.len(), rounds, chals)?;
+    let b_polys = b_polys_of(chunks);
+
+    let mut failures = vec![];
+    // Ranges still to be examined, with whether they are already known to fail.
+    let mut stack = vec![(0..comms.len(), false)];
+    while let Some((range, known_to_fail)) = stack.pop() {
+        if range.is_empty()
+            || (!known_to_fail
+                && random_combination_vanishes(urs, comms, &b_polys, range.clone(), rng))
+        {
+            continue;
+        }
+        if range.len() == 1 {
+            failures.push(range.start);
+            continue;
+        }
+        let mid = range.start + range.len() / 2;
+        let (left, right) = (range.start..mid, mid..range.end);
+        let left_fails = !random_combination_vanishes(urs, comms, &b_polys, left.clone(), rng);
+        // Pushed in this order so that the left half is examined first.
+        stack.push((right, !left_fails));
+        if left_fails {
+            stack.push((left, true));
+        }
     }
+    Ok(failures)
+}
 
-    let rounds = chals.len() / k;
-    assert_eq!(chals.len() % rounds, 0);
+pub fn batch_dlog_accumulator_generate<G: CommitmentCurve>(
+    urs: &SRS<G>,
+    num_comms: usize,
+    rounds: Option<&[usize]>,
+    chals: &Vec<G::ScalarField>,
+) -> Result<Vec<G>, AccumulatorError> {
+    let chunks = split_challenges(urs.g.len(), num_comms, rounds, chals)?;
 
-    let comms: Vec<_> = chals
+    let comms: Vec<_> = chunks
         .into_par_iter()
-        .chunks(rounds)
         .map(|chals| {
-            let chals: Vec<G::ScalarField> = chals.into_iter().map(|x| *x).collect();
-            let scalars: Vec<_> = b_poly_coefficients(&chals)
+            let scalars: Vec<_> = b_poly_coefficients(chals)
                 .into_iter()
                 .map(|x| x.into_repr())
                 .collect();
-            let points: Vec<_> = urs.g.clone();
-            VariableBaseMSM::multi_scalar_mul(&points, &scalars).into_affine()
+            VariableBaseMSM::multi_scalar_mul(&urs.g[..scalars.len()], &scalars).into_affine()
         })
         .collect();
 
-    comms
+    Ok(comms)
+}
+
+/* The `b` polynomial of a set of challenges, `b(X) = prod_i (1 + chal_i X^(2^(k - 1 - i)))`
+ * for `k` challenges. */
+
+/// Evaluates the `b` polynomial of `chals` at `x` with `O(k)` field operations, without
+/// computing its `2^k` coefficients.
+pub fn b_poly_evaluate<F: Field>(chals: &[F], x: F) -> F {
+    let mut res = F::one();
+    let mut x_pow = x;
+    for c in chals.iter().rev() {
+        res *= &(F::one() + &(*c * &x_pow));
+        x_pow.square_in_place();
+    }
+    res
+}
+
+/// Evaluates the `b` polynomial of each set of challenges at each of the `points`, in parallel.
+/// The result holds the evaluations of the `i`th set at `evals[i]`.
+pub fn b_poly_evaluations<F: Field>(chals: &[Vec<F>], points: &[F]) -> Vec<Vec<F>> {
+    chals
+        .par_iter()
+        .map(|chals| points.iter().map(|x| b_poly_evaluate(chals, *x)).collect())
+        .collect()
+}
+
+/// Commits to the `b` polynomial of each set of challenges, in parallel.
+pub fn b_poly_commitments<G: CommitmentCurve>(
+    urs: &SRS<G>,
+    chals: &[Vec<G::ScalarField>],
+) -> Vec<PolyComm<G>> {
+    chals
+        .par_iter()
+        .map(|chals| {
+            let p = DensePolynomial::from_coefficients_vec(b_poly_coefficients(chals));
+            urs.commit_non_hiding(&p, None)
+        })
+        .collect()
+}
+
+/* Resizing. The points of the URS are derived independently of each other and of the depth, so
+ * a URS can be extended by deriving only the missing points, and any prefix of a URS is itself a
+ * URS. Commitments to polynomials that fit in both are the same. */
+
+// The derivation mirrors `SRS::create`: the `i`th point is obtained by mapping the first 31
+// bytes of the Blake2b digest of `i` to the curve.
+fn derived_point<G: CommitmentCurve>(map: &G::Map, i: usize) -> G {
+    const N: usize = 31;
+    let digest = Blake2b::digest(&(i as u32).to_be_bytes());
+    let mut bits = [false; 8 * N];
+    for k in 0..N {
+        for j in 0..8 {
+            bits[8 * k + j] = (digest[k] >> j) & 1 == 1;
+        }
+    }
+    let t = G::BaseField::from_repr(<G::BaseField as PrimeField>::BigInt::from_bits(&bits));
+    let (x, y) = map.to_group(t);
+    G::of_coordinates(x, y)
+}
+
+fn with_points<G: CommitmentCurve>(urs: &SRS<G>, g: Vec<G>) -> SRS<G> {
+    let mut res = SRS::<G>::create(0);
+    res.g = g;
+    res.h = urs.h;
+    res
+}
+
+/// Returns a URS of `depth` points, sharing its first `urs.g.len()` points with `urs`. Returns
+/// `None` if `urs` was not derived by `SRS::create`, as it cannot then be extended consistently.
+pub fn urs_extend<G: CommitmentCurve>(urs: &SRS<G>, depth: usize) -> Option<SRS<G>> {
+    if depth <= urs.g.len() {
+        return Some(urs_trim(urs, depth));
+    }
+    let map = G::Map::setup();
+    if urs
+        .g
+        .first()
+        .map_or(false, |g| *g != derived_point(&map, 0))
+    {
+        return None;
+    }
+    let mut g = urs.g.clone();
+    g.par_extend(
+        (urs.g.len()..depth)
+            .into_par_iter()
+            .map(|i| derived_point(&map, i)),
+    );
+    Some(with_points(urs, g))
+}
+
+/// Returns the URS made of the first `depth` points of `urs`.
+pub fn urs_trim<G: CommitmentCurve>(urs: &SRS<G>, depth: usize) -> SRS<G> {
+    with_points(urs, urs.g[..std::cmp::min(depth, urs.g.len())].to_vec())
+}
+
+/* Fingerprints. */
+
+/// A Blake2s digest of the depth of the URS, `h`, and every point of `g`, in order.
+pub fn urs_digest<G: CommitmentCurve>(urs: &SRS<G>) -> Vec<u8> {
+    let mut hasher = Blake2s::new();
+    let mut buf = vec![];
+    hasher.update(&(urs.g.len() as u64).to_le_bytes());
+    for p in std::iter::once(&urs.h).chain(urs.g.iter()) {
+        buf.clear();
+        // Writing to a `Vec` cannot fail.
+        p.write(&mut buf).unwrap();
+        hasher.update(&buf);
+    }
+    hasher.finalize().to_vec()
+}
+
+/// Checks that the points of `urs` are the ones `SRS::create` derives. If `sample_size` is
+/// given, only that many points of `g`, chosen at random, are checked. Fails if the derivation
+/// here no longer agrees with `SRS::create`, as nothing can then be checked.
+pub fn urs_verify_derivation<G: CommitmentCurve>(
+    urs: &SRS<G>,
+    sample_size: Option<usize>,
+) -> Result<bool, String> {
+    // `h` does not depend on the depth.
+    if urs.h != SRS::<G>::create(0).h {
+        return Ok(false);
+    }
+    let depth = urs.g.len();
+    let indices: Vec<usize> = match sample_size {
+        Some(n) if n < depth => sample(&mut rand_core::OsRng, depth, n).into_vec(),
+        _ => (0..depth).collect(),
+    };
+    let map = G::Map::setup();
+    if SRS::<G>::create(1).g[0] != derived_point(&map, 0) {
+        return Err("urs_verify_derivation: the derivation does not match SRS::create".to_string());
+    }
+    Ok(indices
+        .par_iter()
+        .all(|i| urs.g[*i] == derived_point(&map, *i)))
 }
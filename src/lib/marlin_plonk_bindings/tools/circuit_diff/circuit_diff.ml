@@ -0,0 +1,131 @@
+open Core_kernel
+open Marlin_plonk_bindings
+
+(** Compares two prover indices or two verifier indices, to find out which
+    gates a change to a circuit affected, or why its verification key changed.
+
+    Indices are read as written by [Pasta_fp_index.write] and
+    [Pasta_fp_verifier_index.write], or their [Pasta_fq] counterparts, with
+    the URS saved by [Pasta_fp_urs.save]. [OFFSET] skips a header at the start
+    of both files, such as the one written by the key cache.
+
+    Like [diff], the exit status is 0 if the indices are the same, 1 if they
+    differ and 2 on an error.
+*)
+
+let kind_to_string : Types.Plonk_gate.Kind.t -> string = function
+  | Zero ->
+      "Zero"
+  | Generic ->
+      "Generic"
+  | Poseidon ->
+      "Poseidon"
+  | Add1 ->
+      "Add1"
+  | Add2 ->
+      "Add2"
+  | Vbmul1 ->
+      "Vbmul1"
+  | Vbmul2 ->
+      "Vbmul2"
+  | Vbmul3 ->
+      "Vbmul3"
+  | Endomul1 ->
+      "Endomul1"
+  | Endomul2 ->
+      "Endomul2"
+  | Endomul3 ->
+      "Endomul3"
+  | Endomul4 ->
+      "Endomul4"
+
+let col_to_string : Types.Plonk_gate.Col.t -> string = function
+  | L ->
+      "L"
+  | R ->
+      "R"
+  | O ->
+      "O"
+
+(* Prints the rows that differ, and returns whether the circuits are the
+   same. *)
+let print_circuit_diff (diff : _ Types.Plonk_circuit_diff.t) =
+  let before_rows, after_rows = diff.rows in
+  printf "rows: %d -> %d\n" before_rows after_rows ;
+  Option.iter diff.public_inputs ~f:(fun (before, after) ->
+      printf "public inputs: %d -> %d\n" before after) ;
+  Array.iter diff.gates ~f:(fun (row : _ Types.Plonk_circuit_diff.Row.t) ->
+      let kind (gate : _ Types.Plonk_gate.t option) =
+        Option.value_map gate ~default:"-" ~f:(fun gate ->
+            kind_to_string gate.kind)
+      in
+      let change =
+        match row.change with
+        | Added ->
+            "added"
+        | Removed ->
+            "removed"
+        | Modified ->
+            "modified"
+      in
+      printf "row %d: %s, %s -> %s" row.row change (kind row.before)
+        (kind row.after) ;
+      if not (Array.is_empty row.coefficients) then
+        printf ", coefficients %s"
+          (String.concat_array ~sep:" "
+             (Array.map row.coefficients ~f:Int.to_string)) ;
+      if not (Array.is_empty row.rewired) then
+        printf ", rewired %s"
+          (String.concat_array ~sep:" "
+             (Array.map row.rewired ~f:col_to_string)) ;
+      printf "\n") ;
+  Array.is_empty diff.gates && Option.is_none diff.public_inputs
+
+let print_fields fields =
+  Array.iter fields ~f:(printf "%s differs\n") ;
+  Array.is_empty fields
+
+let usage () =
+  eprintf
+    "Usage: %s (fp|fq) (index|verifier-index) URS BEFORE AFTER [OFFSET]\n"
+    Sys.argv.(0) ;
+  exit 2
+
+let () =
+  match Array.to_list Sys.argv with
+  | _ :: curve :: kind :: urs :: before :: after :: rest ->
+      let offset =
+        match rest with
+        | [] ->
+            None
+        | [ offset ] ->
+            Some (Int.of_string offset)
+        | _ ->
+            usage ()
+      in
+      let same =
+        match (curve, kind) with
+        | "fp", "index" ->
+            let urs = Pasta_fp_urs.load urs in
+            let read path = Pasta_fp_index.read ?offset urs path in
+            print_circuit_diff (Pasta_fp_index.diff (read before) (read after))
+        | "fq", "index" ->
+            let urs = Pasta_fq_urs.load urs in
+            let read path = Pasta_fq_index.read ?offset urs path in
+            print_circuit_diff (Pasta_fq_index.diff (read before) (read after))
+        | "fp", "verifier-index" ->
+            let urs = Pasta_fp_urs.load urs in
+            let read path = Pasta_fp_verifier_index.read ?offset urs path in
+            print_fields
+              (Pasta_fp_verifier_index.diff (read before) (read after))
+        | "fq", "verifier-index" ->
+            let urs = Pasta_fq_urs.load urs in
+            let read path = Pasta_fq_verifier_index.read ?offset urs path in
+            print_fields
+              (Pasta_fq_verifier_index.diff (read before) (read after))
+        | _ ->
+            usage ()
+      in
+      exit (if same then 0 else 1)
+  | _ ->
+      usage ()
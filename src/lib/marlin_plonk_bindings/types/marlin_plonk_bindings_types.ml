@@ -84,6 +84,85 @@ module Plonk_gate = struct
   type 'a t = { kind : Kind.t; wires : Wires.t; c : 'a array }
 end
 
+module Plonk_row_selection = struct
+  type t =
+    | Range of int * int
+        (** The rows from the first to the last, inclusive. The first must be
+            the row of a gate. *)
+    | Neighbourhood of int * int
+        (** The rows within the given number of copy constraints of a row. *)
+end
+
+module Plonk_circuit_stats = struct
+  type t =
+    { rows : int
+          (** Leaving out trailing zero gates wired to themselves, which are
+              padding. *)
+    ; gate_counts : (Plonk_gate.Kind.t * int) array
+          (** For each gate type that occurs in the circuit. *)
+    ; public_inputs : int
+    ; domain_d1_size : int
+    ; domain_d4_size : int
+    ; domain_d8_size : int
+    ; headroom : int
+          (** The number of rows that can be added before the domains double. *)
+    ; cycles : int  (** Cycles of the permutation of length at least 2. *)
+    ; cycle_lengths : (int * int) array
+          (** The number of cycles of each length, by increasing length. *)
+    ; broken_wires : int
+          (** Positions that are not on a cycle of the permutation. *)
+    ; urs_depth_required : int
+          (** To commit to the polynomials without splitting them. *)
+    ; urs_depth : int option
+    }
+end
+
+module Plonk_witness_check = struct
+  module Row = struct
+    type 'field t =
+      { row : int
+      ; kind : Plonk_gate.Kind.t
+      ; l : 'field
+      ; r : 'field
+      ; o : 'field
+      ; holds : bool  (** Whether the constraint of the gate holds. *)
+      ; residual : 'field option
+            (** The value of the constraint of a generic gate, if nonzero. *)
+      ; copies : Plonk_gate.Col.t array
+            (** The wires whose value differs from the one they are wired to. *)
+      }
+  end
+
+  type 'field t =
+    { failures : 'field Row.t array; num_failures : int }
+end
+
+module Plonk_circuit_diff = struct
+  module Change = struct
+    type t = Added | Removed | Modified
+  end
+
+  module Row = struct
+    type 'field t =
+      { row : int
+      ; change : Change.t
+      ; before : 'field Plonk_gate.t option
+      ; after : 'field Plonk_gate.t option
+      ; coefficients : int array
+            (** The indices of the coefficients that differ. *)
+      ; rewired : Plonk_gate.Col.t array
+            (** The wires whose copy constraint differs. *)
+      }
+  end
+
+  type 'field t =
+    { rows : int * int
+    ; public_inputs : (int * int) option
+          (** Before and after, when the number of public inputs differs. *)
+    ; gates : 'field Row.t array  (** The rows that differ, in order. *)
+    }
+end
+
 module Plonk_proof = struct
   module Evaluations = struct
     type 'field t =
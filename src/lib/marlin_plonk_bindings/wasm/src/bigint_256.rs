@@ -14,6 +14,8 @@ const BIGINT256_LIMB_BYTES: i32 = BIGINT256_LIMB_BITS / 8;
 const BIGINT256_NUM_LIMBS: i32 =
     (BIGINT256_NUM_BITS + BIGINT256_LIMB_BITS - 1) / BIGINT256_LIMB_BITS;
 const BIGINT256_NUM_BYTES: usize = (BIGINT256_NUM_LIMBS as usize) * 8;
+// js_of_ocaml ints are 32 bits wide, so this is [max_int].
+const OCAML_MAX_INT: u64 = (1 << 31) - 1;
 
 pub struct WasmBigInteger256(pub BigInteger256);
 
@@ -53,6 +55,29 @@ pub fn of_biguint(x: &BigUint) -> BigInteger256 {
     BigInteger256(*limbs)
 }
 
+/// Reduces `x` modulo 2^256.
+fn of_biguint_wrapping(x: &BigUint) -> BigInteger256 {
+    let mut bytes = x.to_bytes_le();
+    bytes.truncate(BIGINT256_NUM_BYTES);
+    of_biguint(&BigUint::from_bytes_le(&bytes))
+}
+
+fn of_biguint_checked(x: &BigUint) -> Option<BigInteger256> {
+    if x.bits() > BIGINT256_NUM_BITS as usize {
+        None
+    } else {
+        Some(of_biguint(x))
+    }
+}
+
+fn limbwise(x: &BigInteger256, y: &BigInteger256, f: impl Fn(u64, u64) -> u64) -> BigInteger256 {
+    let mut res = BigInteger256::default();
+    for i in 0..(BIGINT256_NUM_LIMBS as usize) {
+        res.0[i] = f(x.0[i], y.0[i]);
+    }
+    res
+}
+
 #[wasm_bindgen]
 pub fn caml_bigint_256_of_numeral(
     s: String,
@@ -92,6 +117,100 @@ pub fn caml_bigint_256_div(
     WasmBigInteger256(of_biguint(&res))
 }
 
+#[wasm_bindgen]
+pub fn caml_bigint_256_mod(x: WasmBigInteger256, y: WasmBigInteger256) -> WasmBigInteger256 {
+    if y.0.is_zero() {
+        panic!("caml_bigint_256_mod");
+    }
+    let res: BigUint = to_biguint(&x.0) % to_biguint(&y.0);
+    WasmBigInteger256(of_biguint(&res))
+}
+
+// wasm-bindgen cannot return tuples, so the carry, borrow and high half are
+// exposed separately and recombined in bindings.js.
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_add(x: WasmBigInteger256, y: WasmBigInteger256) -> WasmBigInteger256 {
+    let mut res = x.0;
+    res.add_nocarry(&y.0);
+    WasmBigInteger256(res)
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_add_carry(x: WasmBigInteger256, y: WasmBigInteger256) -> bool {
+    let mut res = x.0;
+    res.add_nocarry(&y.0)
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_sub(x: WasmBigInteger256, y: WasmBigInteger256) -> WasmBigInteger256 {
+    let mut res = x.0;
+    res.sub_noborrow(&y.0);
+    WasmBigInteger256(res)
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_sub_borrow(x: WasmBigInteger256, y: WasmBigInteger256) -> bool {
+    let mut res = x.0;
+    res.sub_noborrow(&y.0)
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_mul_lo(x: WasmBigInteger256, y: WasmBigInteger256) -> WasmBigInteger256 {
+    let res: BigUint = to_biguint(&x.0) * to_biguint(&y.0);
+    WasmBigInteger256(of_biguint_wrapping(&res))
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_mul_hi(x: WasmBigInteger256, y: WasmBigInteger256) -> WasmBigInteger256 {
+    let res: BigUint = to_biguint(&x.0) * to_biguint(&y.0);
+    WasmBigInteger256(of_biguint(&(res >> BIGINT256_NUM_BITS as usize)))
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_shift_left(x: WasmBigInteger256, n: i32) -> WasmBigInteger256 {
+    if n < 0 {
+        panic!("caml_bigint_256_shift_left");
+    }
+    if n >= BIGINT256_NUM_BITS {
+        return WasmBigInteger256(BigInteger256::default());
+    }
+    let res: BigUint = to_biguint(&x.0) << n as usize;
+    WasmBigInteger256(of_biguint_wrapping(&res))
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_shift_right(x: WasmBigInteger256, n: i32) -> WasmBigInteger256 {
+    if n < 0 {
+        panic!("caml_bigint_256_shift_right");
+    }
+    if n >= BIGINT256_NUM_BITS {
+        return WasmBigInteger256(BigInteger256::default());
+    }
+    let res: BigUint = to_biguint(&x.0) >> n as usize;
+    WasmBigInteger256(of_biguint(&res))
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_logand(x: WasmBigInteger256, y: WasmBigInteger256) -> WasmBigInteger256 {
+    WasmBigInteger256(limbwise(&x.0, &y.0, |a, b| a & b))
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_logor(x: WasmBigInteger256, y: WasmBigInteger256) -> WasmBigInteger256 {
+    WasmBigInteger256(limbwise(&x.0, &y.0, |a, b| a | b))
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_logxor(x: WasmBigInteger256, y: WasmBigInteger256) -> WasmBigInteger256 {
+    WasmBigInteger256(limbwise(&x.0, &y.0, |a, b| a ^ b))
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_num_bits(x: WasmBigInteger256) -> i32 {
+    x.0.num_bits() as i32
+}
+
 #[wasm_bindgen]
 pub fn caml_bigint_256_compare(
     x: WasmBigInteger256,
@@ -114,6 +233,43 @@ pub fn caml_bigint_256_to_string(x: WasmBigInteger256) -> String {
     to_biguint(&x.0).to_string()
 }
 
+#[wasm_bindgen]
+pub fn caml_bigint_256_of_hex_string(s: String) -> WasmBigInteger256 {
+    let s = s
+        .strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(&s);
+    match BigUint::parse_bytes(s.as_bytes(), 16)
+        .as_ref()
+        .and_then(of_biguint_checked)
+    {
+        Some(data) => WasmBigInteger256(data),
+        None => panic!("caml_bigint_256_of_hex_string"),
+    }
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_to_hex_string(x: WasmBigInteger256) -> String {
+    to_biguint(&x.0).to_str_radix(16)
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_of_int(i: i32) -> WasmBigInteger256 {
+    if i < 0 {
+        panic!("caml_bigint_256_of_int");
+    }
+    WasmBigInteger256(BigInteger256::from(i as u64))
+}
+
+#[wasm_bindgen]
+pub fn caml_bigint_256_to_int(x: WasmBigInteger256) -> i32 {
+    let limbs = (x.0).0;
+    if limbs[1..].iter().any(|limb| *limb != 0) || limbs[0] > OCAML_MAX_INT {
+        panic!("caml_bigint_256_to_int: overflow");
+    }
+    limbs[0] as i32
+}
+
 #[wasm_bindgen]
 pub fn caml_bigint_256_test_bit(
     x: WasmBigInteger256,
@@ -1,5 +1,11 @@
 open Core_kernel
 
+(* The digest of the circuit of a prover index is kept in the header, hex
+   encoded, to identify the circuit the index was built for. *)
+let index_digest_to_header digest =
+  String.concat_map (Bytes.to_string digest) ~f:(fun c ->
+      sprintf "%02x" (Char.to_int c))
+
 module Step = struct
   module Key = struct
     module Proving = struct
@@ -31,11 +37,12 @@ module Step = struct
       (fun (_, header, _, cs) ~path ->
         Or_error.try_with_join (fun () ->
             let open Or_error.Let_syntax in
-            let%map header_read, index =
+            let%bind header_read, read_index =
               Snark_keys_header.read_with_header
-                ~read_data:(fun ~offset ->
-                  Marlin_plonk_bindings.Pasta_fp_index.read ~offset
-                    (Backend.Tick.Keypair.load_urs ()))
+                ~read_data:(fun ~offset path ~digest ->
+                  Marlin_plonk_bindings.Pasta_fp_index.read ~offset ~digest
+                    (Backend.Tick.Keypair.load_urs ())
+                    path)
                 path
             in
             [%test_eq: int] header.header_version header_read.header_version ;
@@ -44,15 +51,21 @@ module Step = struct
               header.constraint_constants header_read.constraint_constants ;
             [%test_eq: string] header.constraint_system_hash
               header_read.constraint_system_hash ;
-            { Backend.Tick.Keypair.index; cs }))
+            let digest = Backend.Tick.Keypair.circuit_digest cs in
+            Ok { Backend.Tick.Keypair.index = read_index ~digest; cs }))
       (fun (_, header, _, _) t path ->
         Or_error.try_with (fun () ->
+            let index = t.Backend.Tick.Keypair.index in
+            let index_digest =
+              index_digest_to_header
+                (Marlin_plonk_bindings.Pasta_fp_index.digest index)
+            in
             Snark_keys_header.write_with_header
               ~expected_max_size_log2:33 (* 8 GB should be enough *)
               ~append_data:
-                (Marlin_plonk_bindings.Pasta_fp_index.write ~append:true
-                   t.Backend.Tick.Keypair.index)
-              header path))
+                (Marlin_plonk_bindings.Pasta_fp_index.write ~append:true index)
+              { header with index_digest = Some index_digest }
+              path))
 
   let vk_storable =
     Key_cache.Sync.Disk_storable.simple Key.Verification.to_string
@@ -158,11 +171,12 @@ module Wrap = struct
       (fun (_, header, cs) ~path ->
         Or_error.try_with_join (fun () ->
             let open Or_error.Let_syntax in
-            let%map header_read, index =
+            let%bind header_read, read_index =
               Snark_keys_header.read_with_header
-                ~read_data:(fun ~offset ->
-                  Marlin_plonk_bindings.Pasta_fq_index.read ~offset
-                    (Backend.Tock.Keypair.load_urs ()))
+                ~read_data:(fun ~offset path ~digest ->
+                  Marlin_plonk_bindings.Pasta_fq_index.read ~offset ~digest
+                    (Backend.Tock.Keypair.load_urs ())
+                    path)
                 path
             in
             [%test_eq: int] header.header_version header_read.header_version ;
@@ -171,15 +185,21 @@ module Wrap = struct
               header.constraint_constants header_read.constraint_constants ;
             [%test_eq: string] header.constraint_system_hash
               header_read.constraint_system_hash ;
-            { Backend.Tock.Keypair.index; cs }))
+            let digest = Backend.Tock.Keypair.circuit_digest cs in
+            Ok { Backend.Tock.Keypair.index = read_index ~digest; cs }))
       (fun (_, header, _) t path ->
         Or_error.try_with (fun () ->
+            let index_digest =
+              index_digest_to_header
+                (Marlin_plonk_bindings.Pasta_fq_index.digest t.index)
+            in
             Snark_keys_header.write_with_header
               ~expected_max_size_log2:33 (* 8 GB should be enough *)
               ~append_data:
                 (Marlin_plonk_bindings.Pasta_fq_index.write ~append:true
                    t.index)
-              header path))
+              { header with index_digest = Some index_digest }
+              path))
 
   let read_or_generate step_domains cache k_p k_v typ main =
     let module Vk = Verification_key in
@@ -224,6 +224,7 @@ module Verification_key = struct
         ; commit_date = ""
         ; constraint_system_hash = ""
         ; identifying_hash = ""
+        ; index_digest = None
         }
       in
       let t = lazy (dummy_id, header, Md5.digest_string "") in
@@ -435,6 +436,7 @@ module Make (A : Statement_var_intf) (A_value : Statement_value_intf) = struct
       ; identifying_hash =
           (* TODO: Proper identifying hash. *)
           constraint_system_hash
+      ; index_digest = None
       }
     in
     Timer.start __LOC__ ;
@@ -1191,6 +1193,7 @@ let%test_module "test uncorrelated bulletproof_challenges" =
           ; identifying_hash =
               (* TODO: Proper identifying hash. *)
               constraint_system_hash
+          ; index_digest = None
           }
         in
         let T = Max_branching.eq in
@@ -1960,6 +1963,7 @@ let%test_module "test uncorrelated deferred b" =
           ; identifying_hash =
               (* TODO: Proper identifying hash. *)
               constraint_system_hash
+          ; index_digest = None
           }
         in
         let T = Max_branching.eq in
@@ -141,6 +141,9 @@ type t =
   ; commit_date : string
   ; constraint_system_hash : string
   ; identifying_hash : string
+  ; index_digest : string option [@default None]
+        (** The hex-encoded digest of the circuit of the index in the data, if
+            the data is a prover index. *)
   }
 [@@deriving yojson, sexp, ord, equal]
 
@@ -241,6 +244,7 @@ let%test_module "Check parsing of header" =
       ; commit_date = "2020-01-01 00:00:00.000000Z"
       ; constraint_system_hash = "ABCDEF1234567890"
       ; identifying_hash = "ABCDEF1234567890"
+      ; index_digest = None
       }
 
     let valid_header_string = Yojson.Safe.to_string (to_yojson valid_header)
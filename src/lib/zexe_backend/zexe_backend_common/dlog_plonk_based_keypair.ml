@@ -27,6 +27,8 @@ module type Inputs_intf = sig
     type t
 
     val wrap : t -> Plonk_gate.Wire.t -> Plonk_gate.Wire.t -> unit
+
+    val digest : t -> public:int -> Bytes.t
   end
 
   module Urs : sig
@@ -145,7 +147,9 @@ module Make (Inputs : Inputs_intf) = struct
     in
     (set_urs_info, load)
 
-  let create cs =
+  (* The gates of [cs], wired along its equivalence classes. This finalizes
+     [cs]. *)
+  let gates cs =
     let gates = Constraint_system.finalize_and_get_gates cs in
     let conv =
       Plonk_constraint_system.Row.to_absolute
@@ -158,13 +162,22 @@ module Make (Inputs : Inputs_intf) = struct
           Gate_vector.wrap gates
             { row = conv t.row; col = t.col }
             { row = conv h.row; col = h.col }) ;
+    gates
+
+  let create cs =
     let index =
-      Index.create gates
+      Index.create (gates cs)
         (Set_once.get_exn cs.public_input_size [%here])
         (load_urs ())
     in
     { index; cs }
 
+  (* The digest of the circuit of [cs], which is the digest of the index that
+     [create] builds from it. Like [create], this finalizes [cs]. *)
+  let circuit_digest cs =
+    Gate_vector.digest (gates cs)
+      ~public:(Set_once.get_exn cs.public_input_size [%here])
+
   let vk t = Verifier_index.create t.index
 
   let pk t = t